@@ -0,0 +1,49 @@
+use std::fmt;
+use std::io;
+
+use crate::Signal;
+
+/// Error of `SignalFd` and `Signals`, which tells apart invalid arguments
+/// detected before any syscall from failures reported by the OS.
+///
+/// It can be converted into `std::io::Error`, with `Error::ForbiddenSignal`
+/// becoming an error of `ErrorKind::InvalidInput`, so `?` keeps working in
+/// functions returning `std::io::Result`.
+#[derive(Debug)]
+pub enum Error {
+    /// The signal cannot be accepted via `SignalFd`.
+    ForbiddenSignal(Signal),
+    /// Error returned by the OS.
+    Os(io::Error),
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ForbiddenSignal(signal) => {
+                write!(f, "{:?} cannot be accepted via SignalFd", signal)
+            },
+            Error::Os(err) => err.fmt(f),
+        }
+    }
+}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ForbiddenSignal(_) => None,
+            Error::Os(err) => Some(err),
+        }
+    }
+}
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Os(err)
+    }
+}
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Os(err) => err,
+            err => io::Error::new(io::ErrorKind::InvalidInput, err),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::io::{Result, Error};
+
+use libc::{c_int, c_uint, eventfd, EFD_CLOEXEC, EFD_NONBLOCK, EFD_SEMAPHORE};
+
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+
+use crate::fd::{Fd, read_counter};
+
+/// `EventFd` for async way of cross-task and cross-thread wakeups.
+///
+/// # Example
+///
+/// ```
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let eventfd = EventFd::new(0).unwrap();
+///
+///     eventfd.write(1).unwrap();
+///     eventfd.write(2).unwrap();
+///     assert_eq!(eventfd.read().await.unwrap(), 3);
+///
+///     let err = eventfd.write(u64::MAX).unwrap_err();
+///     assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+/// }
+///
+/// f();
+/// ```
+pub struct EventFd {
+    inner: AsyncFd<Fd>,
+}
+impl EventFd {
+    /// Returns an `EventFd` that is close-on-exec, with its counter
+    /// initialized to `initval`.
+    pub fn new(initval: c_uint) -> Result<Self> {
+        Self::with_flags(initval, 0)
+    }
+
+    /// Same as `EventFd::new`, but with semaphore semantics: `read` waits for
+    /// the counter to be nonzero, then decrements it by one and returns `1`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let eventfd = EventFd::new_semaphore(0).unwrap();
+    ///
+    ///     eventfd.write(3).unwrap();
+    ///     for _ in 0..3 {
+    ///         assert_eq!(eventfd.read().await.unwrap(), 1);
+    ///     }
+    ///
+    ///     let timeout = tokio::time::timeout(Duration::from_millis(10), eventfd.read());
+    ///     assert!(timeout.await.is_err());
+    ///
+    ///     eventfd.write(1).unwrap();
+    ///     assert_eq!(eventfd.read().await.unwrap(), 1);
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn new_semaphore(initval: c_uint) -> Result<Self> {
+        Self::with_flags(initval, EFD_SEMAPHORE)
+    }
+
+    fn with_flags(initval: c_uint, flags: c_int) -> Result<Self> {
+        let fd = unsafe { eventfd(initval, flags | EFD_NONBLOCK | EFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let fd = unsafe { Fd::new(fd) };
+
+        Ok(Self {
+            inner: AsyncFd::with_interest(fd, Interest::READABLE)?,
+        })
+    }
+
+    /// Asynchronously wait for the counter to be nonzero, then return it and
+    /// reset it to zero.
+    ///
+    /// For `EventFd` created via `EventFd::new_semaphore`, decrement the counter
+    /// by one and return `1` instead.
+    pub async fn read(&self) -> Result<u64> {
+        read_counter(&self.inner).await
+    }
+
+    /// Add `val` to the counter.
+    ///
+    /// Fails with `EINVAL` if `val` is `u64::MAX`, or with `WouldBlock` if
+    /// the counter would exceed `u64::MAX - 1`.
+    pub fn write(&self, val: u64) -> Result<()> {
+        self.inner.get_ref().write(&val.to_ne_bytes()).map(|_| ())
+    }
+
+    /// Same as `Fd::close`.
+    pub fn close(self) -> Result<()> {
+        self.inner.into_inner().close()
+    }
+}
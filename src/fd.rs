@@ -1,9 +1,39 @@
-use std::os::unix::io::{AsRawFd, RawFd};
-use std::os::raw::c_void;
-use std::io::{Result, Error};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+use std::os::raw::{c_int, c_void};
+use std::io::{Result, Error, ErrorKind, IoSlice, IoSliceMut, SeekFrom};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use tokio::io::unix::AsyncFd;
+use tokio::io::{AsyncRead, Interest, ReadBuf};
 
 use crate::autorestart;
 
+/// Owned file descriptor that is closed on drop.
+///
+/// # Example
+///
+/// ```
+/// use std::os::unix::io::{FromRawFd, IntoRawFd, OwnedFd, RawFd};
+/// use libc::pipe;
+/// use async_linux_spec_fd::*;
+///
+/// let mut fds: [RawFd; 2] = [-1, -1];
+/// assert_eq!(0, unsafe { pipe(fds.as_mut_ptr()) });
+///
+/// let reader = unsafe { Fd::from_raw_fd(fds[0]) };
+/// let writer: OwnedFd = unsafe { Fd::from_raw_fd(fds[1]) }.into();
+///
+/// // Round-trips without closing the fd.
+/// let reader = unsafe { Fd::from_raw_fd(reader.into_raw_fd()) };
+/// let writer = Fd::from(writer);
+///
+/// assert_eq!(writer.write(b"hello").unwrap(), 5);
+///
+/// let mut buf = [0; 5];
+/// assert_eq!(reader.read(&mut buf).unwrap(), 5);
+/// assert_eq!(&buf, b"hello");
+/// ```
 #[derive(Debug)]
 pub struct Fd {
     inner: RawFd,
@@ -13,23 +43,128 @@ impl AsRawFd for Fd {
         self.inner
     }
 }
+/// Same as `Fd::new`.
+impl FromRawFd for Fd {
+    unsafe fn from_raw_fd(raw_fd: RawFd) -> Self {
+        Self::new(raw_fd)
+    }
+}
+impl IntoRawFd for Fd {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.inner;
+        std::mem::forget(self);
+        fd
+    }
+}
+impl From<OwnedFd> for Fd {
+    fn from(fd: OwnedFd) -> Self {
+        unsafe { Self::new(fd.into_raw_fd()) }
+    }
+}
+impl From<Fd> for OwnedFd {
+    fn from(fd: Fd) -> Self {
+        unsafe { Self::from_raw_fd(fd.into_raw_fd()) }
+    }
+}
 impl Drop for Fd {
     fn drop(&mut self) {
+        // Already closed via `Fd::close`.
+        if self.inner == -1 {
+            return;
+        }
+
         let ret = unsafe {
             libc::close(self.inner)
         };
 
-        if cfg!(debug_assertions) && ret < 0 {
-            let result: Result<()> = Err(Error::last_os_error());
-            result.unwrap();
-        }
+        debug_assert!(ret >= 0, "{}", Error::last_os_error());
     }
 }
 impl Fd {
+    /// # Safety
+    ///
+    /// `raw_fd` must be an open fd owned by nobody else.
     pub const unsafe fn new(raw_fd: RawFd) -> Fd {
         Fd { inner: raw_fd }
     }
 
+    /// Close the fd, returning the error of `close` instead of ignoring it
+    /// like `Drop` does.
+    ///
+    /// The fd is closed even on error, so it is not retried.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::os::unix::io::{AsRawFd, RawFd};
+    /// use libc::{fcntl, pipe, F_GETFD};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut fds: [RawFd; 2] = [-1, -1];
+    /// assert_eq!(0, unsafe { pipe(fds.as_mut_ptr()) });
+    ///
+    /// let reader = unsafe { Fd::new(fds[0]) };
+    /// let writer = unsafe { Fd::new(fds[1]) };
+    ///
+    /// let raw_fd = reader.as_raw_fd();
+    /// reader.close().unwrap();
+    /// assert_eq!(unsafe { fcntl(raw_fd, F_GETFD) }, -1);
+    ///
+    /// // The number is reused by the next fd, which stays open since the
+    /// // closed `Fd` does not close it again on drop.
+    /// let cloned = writer.try_clone().unwrap();
+    /// assert_eq!(cloned.as_raw_fd(), raw_fd);
+    /// assert_ne!(unsafe { fcntl(raw_fd, F_GETFD) }, -1);
+    ///
+    /// cloned.close().unwrap();
+    /// writer.close().unwrap();
+    /// ```
+    pub fn close(mut self) -> Result<()> {
+        let fd = std::mem::replace(&mut self.inner, -1);
+
+        if unsafe { libc::close(fd) } < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Duplicate the fd, returning a new `Fd` that is close-on-exec.
+    ///
+    /// Both `Fd`s refer to the same open file description and are closed
+    /// independently.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::os::unix::io::RawFd;
+    /// use libc::pipe;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut fds: [RawFd; 2] = [-1, -1];
+    /// assert_eq!(0, unsafe { pipe(fds.as_mut_ptr()) });
+    ///
+    /// let reader = unsafe { Fd::new(fds[0]) };
+    /// let writer = unsafe { Fd::new(fds[1]) };
+    ///
+    /// let cloned = reader.try_clone().unwrap();
+    /// drop(reader);
+    ///
+    /// assert_eq!(writer.write(b"hello").unwrap(), 5);
+    ///
+    /// let mut buf = [0; 5];
+    /// cloned.read_exact(&mut buf).unwrap();
+    /// assert_eq!(&buf, b"hello");
+    /// ```
+    pub fn try_clone(&self) -> Result<Fd> {
+        let fd = unsafe { libc::fcntl(self.inner, libc::F_DUPFD_CLOEXEC, 0) };
+        if fd < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(unsafe { Fd::new(fd) })
+        }
+    }
+
     /// Read at most `buf.len()` data into `buf`.
     ///
     /// Auto restart on interrpted.
@@ -48,4 +183,331 @@ impl Fd {
             }
         })
     }
+
+    /// Read exactly `buf.len()` data into `buf`.
+    ///
+    /// Returns `ErrorKind::UnexpectedEof` if eof is reached before `buf` is filled,
+    /// in which case the content of `buf` is unspecified.
+    ///
+    /// Auto restart on interrpted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::os::unix::io::RawFd;
+    /// use libc::pipe;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut fds: [RawFd; 2] = [-1, -1];
+    /// assert_eq!(0, unsafe { pipe(fds.as_mut_ptr()) });
+    ///
+    /// let reader = unsafe { Fd::new(fds[0]) };
+    /// let writer = unsafe { Fd::new(fds[1]) };
+    ///
+    /// let handle = std::thread::spawn(move || {
+    ///     assert_eq!(writer.write(b"hello, ").unwrap(), 7);
+    ///     std::thread::sleep(std::time::Duration::from_millis(10));
+    ///     assert_eq!(writer.write(b"world").unwrap(), 5);
+    /// });
+    ///
+    /// let mut buf = [0; 12];
+    /// reader.read_exact(&mut buf).unwrap();
+    /// assert_eq!(&buf, b"hello, world");
+    ///
+    /// handle.join().unwrap();
+    ///
+    /// // The writer is closed now.
+    /// let err = reader.read_exact(&mut buf).unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    /// ```
+    pub fn read_exact(&self, mut buf: &mut [u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.read(buf)? {
+                0 => return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "failed to fill whole buffer"
+                )),
+                cnt => buf = &mut buf[cnt..],
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write at most `buf.len()` data from `buf`.
+    ///
+    /// Auto restart on interrpted.
+    pub fn write(&self, buf: &[u8]) -> Result<usize> {
+        let buf_ptr = buf.as_ptr() as *const c_void;
+        let buf_len = buf.len() as libc::size_t;
+
+        autorestart!({
+            let result = unsafe {
+                libc::write(self.inner, buf_ptr, buf_len)
+            };
+            if result < 0 {
+                Err(Error::last_os_error())
+            } else {
+                Ok(result as usize)
+            }
+        })
+    }
+
+    /// Read data into `bufs` in order, returning the total bytes read, which
+    /// might be less than the total length of `bufs`.
+    ///
+    /// Panics if `bufs.len()` is greater than `UIO_MAXIOV`.
+    ///
+    /// Auto restart on interrpted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::{IoSlice, IoSliceMut};
+    /// use std::os::unix::io::RawFd;
+    /// use libc::pipe;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut fds: [RawFd; 2] = [-1, -1];
+    /// assert_eq!(0, unsafe { pipe(fds.as_mut_ptr()) });
+    ///
+    /// let reader = unsafe { Fd::new(fds[0]) };
+    /// let writer = unsafe { Fd::new(fds[1]) };
+    ///
+    /// let cnt = writer.writev(&[IoSlice::new(b"head"), IoSlice::new(b"body")]).unwrap();
+    /// assert_eq!(cnt, 8);
+    ///
+    /// let mut head = [0; 4];
+    /// let mut body = [0; 4];
+    /// let cnt = reader.readv(&mut [IoSliceMut::new(&mut head), IoSliceMut::new(&mut body)]);
+    /// assert_eq!(cnt.unwrap(), 8);
+    /// assert_eq!(&head, b"head");
+    /// assert_eq!(&body, b"body");
+    /// ```
+    pub fn readv(&self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        assert!(bufs.len() <= libc::UIO_MAXIOV as usize);
+
+        // `IoSliceMut` is guaranteed to be ABI compatible with `iovec`.
+        let iov = bufs.as_mut_ptr() as *const libc::iovec;
+        let iovcnt = bufs.len() as libc::c_int;
+
+        autorestart!({
+            let result = unsafe { libc::readv(self.inner, iov, iovcnt) };
+            if result < 0 {
+                Err(Error::last_os_error())
+            } else {
+                Ok(result as usize)
+            }
+        })
+    }
+
+    /// Write data from `bufs` in order, returning the total bytes written,
+    /// which might be less than the total length of `bufs`.
+    ///
+    /// Panics if `bufs.len()` is greater than `UIO_MAXIOV`.
+    ///
+    /// Auto restart on interrpted.
+    pub fn writev(&self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        assert!(bufs.len() <= libc::UIO_MAXIOV as usize);
+
+        // `IoSlice` is guaranteed to be ABI compatible with `iovec`.
+        let iov = bufs.as_ptr() as *const libc::iovec;
+        let iovcnt = bufs.len() as libc::c_int;
+
+        autorestart!({
+            let result = unsafe { libc::writev(self.inner, iov, iovcnt) };
+            if result < 0 {
+                Err(Error::last_os_error())
+            } else {
+                Ok(result as usize)
+            }
+        })
+    }
+
+    /// Reposition the file offset and return the new offset from the start
+    /// of the file.
+    pub fn seek(&self, pos: SeekFrom) -> Result<u64> {
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (offset as libc::off_t, libc::SEEK_SET),
+            SeekFrom::End(offset) => (offset as libc::off_t, libc::SEEK_END),
+            SeekFrom::Current(offset) => (offset as libc::off_t, libc::SEEK_CUR),
+        };
+
+        let result = unsafe { libc::lseek(self.inner, offset, whence) };
+        if result < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(result as u64)
+        }
+    }
+
+    /// Truncate or extend the file to `len` bytes.
+    ///
+    /// Auto restart on interrpted.
+    pub fn ftruncate(&self, len: u64) -> Result<()> {
+        autorestart!({
+            if unsafe { libc::ftruncate(self.inner, len as libc::off_t) } < 0 {
+                Err(Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    /// Test if the fd is readable right now via `poll` with zero timeout.
+    pub(crate) fn is_readable(&self) -> Result<bool> {
+        let mut pollfd = libc::pollfd {
+            fd: self.inner,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        autorestart!({
+            if unsafe { libc::poll(&mut pollfd, 1, 0) } < 0 {
+                Err(Error::last_os_error())
+            } else {
+                Ok(pollfd.revents & libc::POLLIN != 0)
+            }
+        })
+    }
+
+    fn update_flags(&self, get: c_int, set: c_int, flag: c_int, on: bool) -> Result<()> {
+        let flags = unsafe { libc::fcntl(self.inner, get) };
+        if flags < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let new_flags = if on { flags | flag } else { flags & !flag };
+        if new_flags != flags && unsafe { libc::fcntl(self.inner, set, new_flags) } < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set or clear `O_NONBLOCK`, keeping the other file status flags.
+    ///
+    /// `Fd` must be nonblocking to be used with `tokio::io::unix::AsyncFd`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::ErrorKind;
+    /// use std::os::unix::io::RawFd;
+    /// use libc::pipe;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut fds: [RawFd; 2] = [-1, -1];
+    /// assert_eq!(0, unsafe { pipe(fds.as_mut_ptr()) });
+    ///
+    /// let reader = unsafe { Fd::new(fds[0]) };
+    /// let _writer = unsafe { Fd::new(fds[1]) };
+    ///
+    /// reader.set_nonblocking(true).unwrap();
+    /// reader.set_cloexec(true).unwrap();
+    ///
+    /// let mut buf = [0; 1];
+    /// assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    /// ```
+    pub fn set_nonblocking(&self, on: bool) -> Result<()> {
+        self.update_flags(libc::F_GETFL, libc::F_SETFL, libc::O_NONBLOCK, on)
+    }
+
+    /// Set or clear `FD_CLOEXEC`, keeping the other file descriptor flags.
+    pub fn set_cloexec(&self, on: bool) -> Result<()> {
+        self.update_flags(libc::F_GETFD, libc::F_SETFD, libc::FD_CLOEXEC, on)
+    }
+}
+
+/// Read from `inner` into `buf` once it is readable.
+pub(crate) fn poll_read_buf(inner: &AsyncFd<Fd>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>)
+    -> Poll<Result<()>>
+{
+    loop {
+        let mut guard = ready!(inner.poll_read_ready(cx))?;
+
+        let unfilled = buf.initialize_unfilled();
+        match guard.try_io(|inner| inner.get_ref().read(unfilled)) {
+            Ok(result) => {
+                buf.advance(result?);
+                break Poll::Ready(Ok(()));
+            },
+            Err(_would_block) => continue,
+        }
+    }
+}
+
+/// Read the 8-byte counter of eventfd or timerfd once it is readable.
+pub(crate) async fn read_counter(inner: &AsyncFd<Fd>) -> Result<u64> {
+    let mut buffer = [0_u8; 8];
+
+    loop {
+        let mut guard = inner.readable().await?;
+
+        match guard.try_io(|inner| inner.get_ref().read(&mut buffer)) {
+            Ok(result) => break result.map(|_| u64::from_ne_bytes(buffer)),
+            Err(_would_block) => continue,
+        }
+    }
+}
+
+/// `Fd` registered in tokio, implementing `AsyncRead`.
+///
+/// # Example
+///
+/// ```
+/// use std::os::unix::io::RawFd;
+/// use libc::{pipe2, write, c_void, O_CLOEXEC, O_NONBLOCK};
+/// use tokio::io::AsyncReadExt;
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let mut fds: [RawFd; 2] = [-1, -1];
+///     assert_eq!(0, unsafe { pipe2(fds.as_mut_ptr(), O_NONBLOCK | O_CLOEXEC) });
+///
+///     let mut reader = AsyncReadFd::new(unsafe { Fd::new(fds[0]) }).unwrap();
+///     let writer = unsafe { Fd::new(fds[1]) };
+///
+///     assert_eq!(5, unsafe { write(fds[1], b"hello".as_ptr() as *const c_void, 5) });
+///     drop(writer);
+///
+///     let mut buf = Vec::new();
+///     reader.read_to_end(&mut buf).await.unwrap();
+///     assert_eq!(buf, b"hello");
+/// }
+///
+/// f();
+/// ```
+#[derive(Debug)]
+pub struct AsyncReadFd {
+    inner: AsyncFd<Fd>,
+}
+impl AsyncReadFd {
+    /// `fd` must be nonblocking.
+    pub fn new(fd: Fd) -> Result<Self> {
+        Ok(Self {
+            inner: AsyncFd::with_interest(fd, Interest::READABLE)?,
+        })
+    }
+
+    pub fn get_ref(&self) -> &Fd {
+        self.inner.get_ref()
+    }
+
+    pub fn into_inner(self) -> Fd {
+        self.inner.into_inner()
+    }
+
+    /// Same as `Fd::close`.
+    pub fn close(self) -> Result<()> {
+        self.into_inner().close()
+    }
+}
+impl AsyncRead for AsyncReadFd {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>)
+        -> Poll<Result<()>>
+    {
+        poll_read_buf(&self.inner, cx, buf)
+    }
 }
@@ -0,0 +1,224 @@
+use std::ffi::{CString, OsStr, OsString};
+use std::io::{Result, Error, ErrorKind};
+use std::mem::size_of;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+
+use libc::{c_int, inotify_event, inotify_init1, IN_CLOEXEC, IN_NONBLOCK};
+
+use bitflags::bitflags;
+
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+
+use crate::fd::Fd;
+
+bitflags! {
+    /// Events to watch for in `Inotify::add_watch` and events reported in
+    /// `Event::mask`.
+    pub struct WatchMask: u32 {
+        /// File was accessed.
+        const ACCESS = libc::IN_ACCESS;
+        /// File was modified.
+        const MODIFY = libc::IN_MODIFY;
+        /// Metadata changed.
+        const ATTRIB = libc::IN_ATTRIB;
+        /// File opened for writing was closed.
+        const CLOSE_WRITE = libc::IN_CLOSE_WRITE;
+        /// File not opened for writing was closed.
+        const CLOSE_NOWRITE = libc::IN_CLOSE_NOWRITE;
+        /// File was opened.
+        const OPEN = libc::IN_OPEN;
+        /// File was moved out of the watched directory.
+        const MOVED_FROM = libc::IN_MOVED_FROM;
+        /// File was moved into the watched directory.
+        const MOVED_TO = libc::IN_MOVED_TO;
+        /// File was created in the watched directory.
+        const CREATE = libc::IN_CREATE;
+        /// File was deleted from the watched directory.
+        const DELETE = libc::IN_DELETE;
+        /// Watched file or directory was deleted.
+        const DELETE_SELF = libc::IN_DELETE_SELF;
+        /// Watched file or directory was moved.
+        const MOVE_SELF = libc::IN_MOVE_SELF;
+        /// All of the events above.
+        const ALL_EVENTS = libc::IN_ALL_EVENTS;
+
+        /// Only watch `path` if it is a directory.
+        const ONLYDIR = libc::IN_ONLYDIR;
+        /// Do not follow `path` if it is a symlink.
+        const DONT_FOLLOW = libc::IN_DONT_FOLLOW;
+        /// Stop reporting events for children once they are unlinked.
+        const EXCL_UNLINK = libc::IN_EXCL_UNLINK;
+        /// Add to the mask of an existing watch instead of replacing it.
+        const MASK_ADD = libc::IN_MASK_ADD;
+        /// Remove the watch after one event.
+        const ONESHOT = libc::IN_ONESHOT;
+
+        /// Watch was removed, either explicitly or because the file is gone.
+        const IGNORED = libc::IN_IGNORED;
+        /// Subject of the event is a directory.
+        const ISDIR = libc::IN_ISDIR;
+        /// Event queue overflowed and events were dropped.
+        const Q_OVERFLOW = libc::IN_Q_OVERFLOW;
+        /// Filesystem containing the watched object was unmounted.
+        const UNMOUNT = libc::IN_UNMOUNT;
+    }
+}
+
+/// Watch returned by `Inotify::add_watch`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct WatchDescriptor(c_int);
+impl WatchDescriptor {
+    /// Returns the raw watch descriptor.
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+/// Filesystem event read from `Inotify`.
+#[derive(Clone, Debug)]
+pub struct Event {
+    /// Watch the event is for.
+    pub wd: WatchDescriptor,
+    /// Events that occurred, along with flags like `WatchMask::ISDIR`.
+    pub mask: WatchMask,
+    /// Connects `MOVED_FROM` and `MOVED_TO` events of the same rename.
+    pub cookie: u32,
+    /// Name of the file inside the watched directory, `None` if the event
+    /// is for the watched object itself.
+    pub name: Option<OsString>,
+}
+
+/// `Inotify` for async way of watching filesystem events.
+///
+/// # Example
+///
+/// ```
+/// use std::fs;
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let dir = std::env::temp_dir().join(format!("inotify-{}", std::process::id()));
+///     fs::create_dir(&dir).unwrap();
+///
+///     let inotify = Inotify::new().unwrap();
+///     let wd = inotify.add_watch(&dir, WatchMask::CREATE).unwrap();
+///
+///     fs::File::create(dir.join("file")).unwrap();
+///
+///     let events = inotify.read().await.unwrap();
+///     assert_eq!(events.len(), 1);
+///     assert_eq!(events[0].wd, wd);
+///     assert!(events[0].mask.contains(WatchMask::CREATE));
+///     assert_eq!(events[0].name.as_deref(), Some("file".as_ref()));
+///
+///     inotify.rm_watch(wd).unwrap();
+///     fs::remove_dir_all(&dir).unwrap();
+/// }
+///
+/// f();
+/// ```
+pub struct Inotify {
+    inner: AsyncFd<Fd>,
+}
+impl Inotify {
+    /// Returns an `Inotify` that is close-on-exec.
+    pub fn new() -> Result<Self> {
+        let fd = unsafe { inotify_init1(IN_NONBLOCK | IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let fd = unsafe { Fd::new(fd) };
+
+        Ok(Self {
+            inner: AsyncFd::with_interest(fd, Interest::READABLE)?,
+        })
+    }
+
+    /// Watch `path` for events in `mask`.
+    ///
+    /// Watching the same inode again returns the same `WatchDescriptor` and
+    /// replaces its mask, unless `WatchMask::MASK_ADD` is specified.
+    pub fn add_watch<P: AsRef<Path>>(&self, path: P, mask: WatchMask)
+        -> Result<WatchDescriptor>
+    {
+        let path = CString::new(path.as_ref().as_os_str().as_bytes())
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+
+        let fd = self.inner.get_ref().as_raw_fd();
+
+        let wd = unsafe { libc::inotify_add_watch(fd, path.as_ptr(), mask.bits()) };
+        if wd < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(WatchDescriptor(wd))
+        }
+    }
+
+    /// Remove the watch, which generates an event of `WatchMask::IGNORED`.
+    pub fn rm_watch(&self, wd: WatchDescriptor) -> Result<()> {
+        let fd = self.inner.get_ref().as_raw_fd();
+
+        if unsafe { libc::inotify_rm_watch(fd, wd.0) } < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Asynchronously wait for events and return all of them that are ready.
+    pub async fn read(&self) -> Result<Vec<Event>> {
+        // Large enough for at least one event with the longest name.
+        let mut buffer = [0_u8; 4096];
+
+        let cnt = loop {
+            let mut guard = self.inner.readable().await?;
+
+            match guard.try_io(|inner| inner.get_ref().read(&mut buffer)) {
+                Ok(result) => break result?,
+                Err(_would_block) => continue,
+            }
+        };
+
+        Ok(parse_events(&buffer[..cnt]))
+    }
+
+    /// Same as `Fd::close`.
+    pub fn close(self) -> Result<()> {
+        self.inner.into_inner().close()
+    }
+}
+
+/// Walk the `inotify_event`s in `buffer`, each of which is followed by a
+/// nul-padded name of `len` bytes.
+fn parse_events(mut buffer: &[u8]) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    while buffer.len() >= size_of::<inotify_event>() {
+        let event = unsafe {
+            (buffer.as_ptr() as *const inotify_event).read_unaligned()
+        };
+
+        let (name, rest) = buffer[size_of::<inotify_event>()..].split_at(event.len as usize);
+        buffer = rest;
+
+        let name = name.split(|byte| *byte == 0).next().unwrap_or_default();
+
+        events.push(Event {
+            wd: WatchDescriptor(event.wd),
+            mask: WatchMask::from_bits_truncate(event.mask),
+            cookie: event.cookie,
+            name: if name.is_empty() {
+                None
+            } else {
+                Some(OsStr::from_bytes(name).to_os_string())
+            },
+        });
+    }
+
+    events
+}
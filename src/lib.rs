@@ -1,18 +1,34 @@
 extern crate libc;
 extern crate tokio;
 extern crate arrayvec;
-extern crate num_enum;
+extern crate bitflags;
+extern crate futures_core;
 
 pub use libc::{pid_t, siginfo_t};
 
+mod error;
 mod signal;
 mod signal_mask;
 pub mod utility;
 mod fd;
 mod signal_fd;
+mod sig_info;
 mod pid_fd;
+mod event_fd;
+mod timer_fd;
+mod mem_fd;
+mod session;
+mod inotify;
 
-pub use signal::Signal;
-pub use signal_mask::SignalMask;
+pub use error::Error;
+pub use signal::{Signal, UnknownSignal};
+pub use signal_mask::{SignalMask, How, BlockGuard};
+pub use fd::{Fd, AsyncReadFd};
 pub use signal_fd::*;
+pub use sig_info::SigInfo;
 pub use pid_fd::*;
+pub use event_fd::EventFd;
+pub use timer_fd::TimerFd;
+pub use mem_fd::*;
+pub use session::*;
+pub use inotify::{Inotify, WatchMask, WatchDescriptor, Event};
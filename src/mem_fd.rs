@@ -0,0 +1,55 @@
+use std::ffi::CStr;
+use std::io::{Result, Error};
+
+use libc::c_uint;
+
+use bitflags::bitflags;
+
+use crate::fd::Fd;
+
+bitflags! {
+    /// Flags for `memfd_create`.
+    pub struct MemfdFlags: c_uint {
+        /// Set close-on-exec on the fd.
+        const CLOEXEC = libc::MFD_CLOEXEC;
+        /// Allow sealing operations via `fcntl(F_ADD_SEALS)` on the fd.
+        const ALLOW_SEALING = libc::MFD_ALLOW_SEALING;
+    }
+}
+
+/// Create an anonymous file that lives in memory.
+///
+/// The returned `Fd` is a regular file that is always ready for read and
+/// write, thus it is not registered in tokio.
+///
+///  * `name` - used as the name of the symlink in `/proc/self/fd/` and
+///    for debugging only.
+///
+/// # Example
+///
+/// ```
+/// use std::ffi::CStr;
+/// use std::io::SeekFrom;
+/// use async_linux_spec_fd::*;
+///
+/// let name = CStr::from_bytes_with_nul(b"buffer\0").unwrap();
+/// let fd = memfd_create(name, MemfdFlags::CLOEXEC).unwrap();
+///
+/// assert_eq!(fd.write(b"hello").unwrap(), 5);
+/// assert_eq!(fd.seek(SeekFrom::Start(0)).unwrap(), 0);
+///
+/// let mut buf = [0; 5];
+/// assert_eq!(fd.read(&mut buf).unwrap(), 5);
+/// assert_eq!(&buf, b"hello");
+///
+/// fd.ftruncate(0).unwrap();
+/// assert_eq!(fd.read(&mut buf).unwrap(), 0);
+/// ```
+pub fn memfd_create(name: &CStr, flags: MemfdFlags) -> Result<Fd> {
+    let fd = unsafe { libc::memfd_create(name.as_ptr(), flags.bits()) };
+    if fd < 0 {
+        Err(Error::last_os_error())
+    } else {
+        Ok(unsafe { Fd::new(fd) })
+    }
+}
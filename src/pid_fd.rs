@@ -1,16 +1,21 @@
 use std::convert::TryFrom;
-use std::io::{Result, Error};
-use std::os::unix::io::{AsRawFd, RawFd};
+use std::fmt;
+use std::io::{Result, Error, ErrorKind};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
 use std::ptr::null;
 use std::mem::MaybeUninit;
+use std::time::Duration;
 
 use libc::{c_int, c_uint, syscall};
 
+use bitflags::bitflags;
+
 use tokio::io::unix::AsyncFd;
 use tokio::io::Interest;
+use tokio::sync::OnceCell;
 
 use crate::fd::Fd;
-use crate::{pid_t, Signal, siginfo_t};
+use crate::{pid_t, Signal, SignalFd, siginfo_t};
 
 fn waitid(idtype: libc::idtype_t, id: libc::id_t, options: c_int)
     -> Result<Option<libc::siginfo_t>>
@@ -32,6 +37,382 @@ fn waitid(idtype: libc::idtype_t, id: libc::id_t, options: c_int)
     }
 }
 
+/// Children to wait for in `reap`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IdType {
+    /// Any child.
+    All,
+    /// The child with the pid.
+    Pid(pid_t),
+    /// Any child in the process group.
+    Pgid(pid_t),
+    /// The child referred to by the pidfd.
+    PidFd(RawFd),
+}
+
+/// Reap a child specified by `idtype` that has changed into one of the states
+/// specified in `options`, without blocking.
+///
+/// Returns `None` if no such child has changed state yet, or an error of
+/// `ECHILD` if there is no such child at all.
+///
+/// Like `PidFd::wait_for_state`, the child is not reaped if
+/// `WaitOptions::NOWAIT` is specified.
+///
+/// # Example
+///
+/// ```
+/// use libc::{fork, setpgid, _exit};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     for exit_code in 0..2 {
+///         let pid = unsafe { fork() };
+///         assert!(pid >= 0);
+///         if pid == 0 { // child
+///             unsafe {
+///                 setpgid(0, 0);
+///                 _exit(exit_code);
+///             }
+///         }
+///         unsafe { setpgid(pid, pid) };
+///
+///         PidFd::open(pid).unwrap().wait_for_terminate().await.unwrap();
+///
+///         let idtype = if exit_code == 0 {
+///             IdType::Pid(pid)
+///         } else {
+///             IdType::Pgid(pid)
+///         };
+///         match reap(idtype, WaitOptions::EXITED).unwrap().unwrap() {
+///             StateChange::Exited(exit_info) => match exit_info.get_code() {
+///                 ExitCode::Exited(code) => assert_eq!(code, exit_code),
+///                 code => panic!("Unexpected exit code {:#?}", code),
+///             },
+///             state => panic!("Unexpected state {:#?}", state),
+///         }
+///     }
+/// }
+///
+/// f();
+/// ```
+pub fn reap(idtype: IdType, options: WaitOptions) -> Result<Option<StateChange>> {
+    let (idtype, id) = match idtype {
+        IdType::All => (libc::P_ALL, 0),
+        IdType::Pid(pid) => (libc::P_PID, pid as libc::id_t),
+        IdType::Pgid(pgid) => (libc::P_PGID, pgid as libc::id_t),
+        IdType::PidFd(pidfd) => (libc::P_PIDFD, pidfd as libc::id_t),
+    };
+
+    let siginfo = waitid(idtype, id, options.bits() | libc::WNOHANG)?;
+
+    Ok(siginfo.map(|siginfo| unsafe { StateChange::new(siginfo) }))
+}
+
+/// Asynchronously wait for a child in process group `pgid` to terminate and
+/// reap it.
+///
+/// Returns `None` once there is no child left in the group.
+///
+/// `signal_fd` must accept `SIGCHLD` only, otherwise it fails with
+/// `ErrorKind::InvalidInput`, since the siginfos read from it are discarded
+/// as they are only used as a cue to check again.
+///
+/// Since `SIGCHLD` can be consumed by another reader first, e.g. a concurrent
+/// call sharing `signal_fd`, it also checks again every 10ms.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use libc::{fork, pid_t, setpgid, _exit};
+/// use async_linux_spec_fd::*;
+///
+/// fn spawn_group(exit_codes: &[i32], delay: Duration) -> pid_t {
+///     let mut pgid = 0;
+///
+///     for &exit_code in exit_codes {
+///         let pid = unsafe { fork() };
+///         assert!(pid >= 0);
+///         if pid == 0 { // child
+///             unsafe { setpgid(0, pgid) };
+///             std::thread::sleep(delay);
+///             unsafe { _exit(exit_code) };
+///         }
+///         unsafe { setpgid(pid, pgid) };
+///
+///         if pgid == 0 {
+///             pgid = pid;
+///         }
+///     }
+///
+///     pgid
+/// }
+///
+/// async fn reap_group(pgid: pid_t, signal_fd: &SignalFd) -> Vec<i32> {
+///     let mut exit_codes = Vec::new();
+///     while let Some(exit_info) = wait_pgid(pgid, signal_fd).await.unwrap() {
+///         match exit_info.get_code() {
+///             ExitCode::Exited(code) => exit_codes.push(code),
+///             code => panic!("Unexpected exit code {:#?}", code),
+///         }
+///     }
+///
+///     exit_codes.sort_unstable();
+///     exit_codes
+/// }
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let signal_fd = Signals::new().add(Signal::Sigchld).into_signalfd().unwrap();
+///
+///     let pgid1 = spawn_group(&[0, 1, 2], Duration::ZERO);
+///     let pgid2 = spawn_group(&[3, 4], Duration::from_millis(100));
+///
+///     let (exit_codes1, exit_codes2) = tokio::join!(
+///         reap_group(pgid1, &signal_fd),
+///         reap_group(pgid2, &signal_fd),
+///     );
+///     assert_eq!(exit_codes1, [0, 1, 2]);
+///     assert_eq!(exit_codes2, [3, 4]);
+///
+///     // Other signals accepted by the `SignalFd` would be discarded.
+///     signal_fd.set_mask({
+///         let mut signal_mask = SignalMask::new();
+///         signal_mask.add(Signal::Sigchld).unwrap();
+///         signal_mask.add(Signal::Sigterm).unwrap();
+///         signal_mask
+///     }).unwrap();
+///
+///     let err = wait_pgid(pgid1, &signal_fd).await.unwrap_err();
+///     assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+/// }
+///
+/// f();
+/// ```
+pub async fn wait_pgid(pgid: pid_t, signal_fd: &SignalFd) -> Result<Option<ExitInfo>> {
+    if signal_fd.raw_mask()? != 1 << (libc::SIGCHLD - 1) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "signal_fd must accept SIGCHLD only"
+        ));
+    }
+
+    loop {
+        match waitid(libc::P_PGID, pgid as libc::id_t, libc::WEXITED | libc::WNOHANG) {
+            Ok(Some(siginfo)) => break Ok(Some(unsafe { ExitInfo::new(siginfo) })),
+            Ok(None) => {
+                let drain = tokio::time::timeout(STATE_POLL_INTERVAL, signal_fd.drain());
+                if let Ok(result) = drain.await {
+                    result?;
+                }
+            },
+            Err(err) if err.raw_os_error() == Some(libc::ECHILD) => break Ok(None),
+            Err(err) => break Err(err),
+        }
+    }
+}
+
+/// Send `signal` to every process referred to by `pidfds` via `PidFd::send_signal`,
+/// returning the result of each of them in the same order.
+///
+/// Failing to send to one of them does not stop sending to the rest.
+///
+/// # Example
+///
+/// ```
+/// use libc::{fork, pause};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let mut pidfds = Vec::new();
+///
+///     for _ in 0..3 {
+///         let pid = unsafe { fork() };
+///         assert!(pid >= 0);
+///         if pid == 0 { // child
+///             loop {
+///                 unsafe { pause() };
+///             }
+///         }
+///         pidfds.push(PidFd::open(pid).unwrap());
+///     }
+///
+///     let pidfd_refs: Vec<&PidFd> = pidfds.iter().collect();
+///     for result in send_signal_all(&pidfd_refs, Signal::Sigterm) {
+///         result.unwrap();
+///     }
+///
+///     for pidfd in &pidfds {
+///         match pidfd.waitpid().await.unwrap().get_code() {
+///             ExitCode::Killed(signal) => assert_eq!(signal, Signal::Sigterm),
+///             code => panic!("Unexpected exit code {:#?}", code),
+///         }
+///     }
+/// }
+///
+/// f();
+/// ```
+pub fn send_signal_all(pidfds: &[&PidFd], signal: Signal) -> Vec<Result<()>> {
+    pidfds
+        .iter()
+        .map(|pidfd| pidfd.send_signal(signal, None))
+        .collect()
+}
+
+/// Outcome of `wait_or_signal`.
+///
+/// # Example
+///
+/// ```
+/// use libc::{fork, _exit};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let signal_fd = Signals::new().add(Signal::Sigusr2).into_signalfd().unwrap();
+///
+///     let pid = unsafe { fork() };
+///     assert!(pid >= 0);
+///     if pid == 0 { // child
+///         unsafe { _exit(5) };
+///     }
+///
+///     let pidfd = PidFd::open(pid).unwrap();
+///
+///     match wait_or_signal(&pidfd, &signal_fd).await.unwrap() {
+///         WaitOutcome::Exited(exit_info) => {
+///             assert_eq!(exit_info.get_code(), ExitCode::Exited(5))
+///         },
+///         outcome => panic!("Unexpected outcome {:#?}", outcome),
+///     }
+/// }
+///
+/// f();
+/// ```
+#[derive(Copy, Clone)]
+pub enum WaitOutcome {
+    /// The child terminated and has been reaped.
+    Exited(ExitInfo),
+    /// A signal arrived via the `SignalFd` before the child terminated.
+    Signalled(libc::signalfd_siginfo),
+}
+impl fmt::Debug for WaitOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitOutcome::Exited(exit_info) => {
+                f.debug_tuple("Exited").field(exit_info).finish()
+            },
+            WaitOutcome::Signalled(siginfo) => {
+                f.debug_tuple("Signalled").field(&siginfo.ssi_signo).finish()
+            },
+        }
+    }
+}
+
+/// Wait for the child referred to by `pidfd` to terminate, or for a signal
+/// to arrive via `signal_fd`, whichever comes first.
+///
+/// If both are ready, the child termination is preferred.
+///
+/// Both branches are cancel-safe, so the branch that loses the race is left
+/// untouched: the child is not reaped and no signal is consumed.
+///
+/// # Example
+///
+/// ```
+/// use libc::{fork, getpid, kill, pause};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let signal_fd = Signals::new().add(Signal::Sigusr1).into_signalfd().unwrap();
+///
+///     let pid = unsafe { fork() };
+///     assert!(pid >= 0);
+///     if pid == 0 { // child
+///         loop {
+///             unsafe { pause() };
+///         }
+///     }
+///
+///     let pidfd = PidFd::open(pid).unwrap();
+///
+///     assert_eq!(0, unsafe { kill(getpid(), Signal::Sigusr1.into()) });
+///
+///     match wait_or_signal(&pidfd, &signal_fd).await.unwrap() {
+///         WaitOutcome::Signalled(siginfo) => {
+///             assert_eq!(siginfo.ssi_signo as i32, Signal::Sigusr1.into())
+///         },
+///         outcome => panic!("Unexpected outcome {:#?}", outcome),
+///     }
+///
+///     pidfd.send_signal(Signal::Sigkill, None).unwrap();
+///     pidfd.waitpid().await.unwrap();
+/// }
+///
+/// f();
+/// ```
+pub async fn wait_or_signal(pidfd: &PidFd, signal_fd: &SignalFd) -> Result<WaitOutcome> {
+    tokio::select! {
+        biased;
+
+        exit_info = pidfd.waitpid() => exit_info.map(WaitOutcome::Exited),
+        siginfo = signal_fd.read_one() => siginfo.map(WaitOutcome::Signalled),
+    }
+}
+
+/// Lower bound of the delays between attempts in `PidFd::wait_polling`.
+const MIN_POLL_DELAY: Duration = Duration::from_millis(1);
+
+/// How often `PidFd::wait_for_state` checks for stop/continue, which unlike
+/// termination do not make the pidfd readable.
+///
+/// Also how often `wait_pgid` checks again without `SIGCHLD`.
+const STATE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+bitflags! {
+    /// State changes to wait for in `PidFd::wait_for_state`.
+    pub struct WaitOptions: c_int {
+        /// Wait for the process to terminate.
+        const EXITED = libc::WEXITED;
+        /// Wait for the process to be stopped by a signal.
+        const STOPPED = libc::WSTOPPED;
+        /// Wait for the stopped process to be resumed by `SIGCONT`.
+        const CONTINUED = libc::WCONTINUED;
+        /// Leave the process in a waitable state, so that the same state change
+        /// can be observed again by a later call.
+        const NOWAIT = libc::WNOWAIT;
+    }
+}
+
+bitflags! {
+    /// Flags for `PidFd::send_signal_with_flags`.
+    ///
+    /// Requires Linux 6.9.
+    pub struct SendSignalFlags: c_uint {
+        /// Send the signal to the specific thread referred to by the pidfd.
+        const THREAD = libc::PIDFD_SIGNAL_THREAD;
+        /// Send the signal to the thread group, as `kill` does.
+        const THREAD_GROUP = libc::PIDFD_SIGNAL_THREAD_GROUP;
+        /// Send the signal to the process group, as `killpg` does.
+        const PROCESS_GROUP = libc::PIDFD_SIGNAL_PROCESS_GROUP;
+    }
+}
+
+bitflags! {
+    /// Flags for `PidFd::open_with_flags`.
+    pub struct PidFdFlags: c_uint {
+        /// Make `waitid` on the pidfd fail with `EAGAIN` instead of blocking
+        /// while the process is still alive.
+        ///
+        /// Requires Linux 5.10.
+        const NONBLOCK = libc::PIDFD_NONBLOCK;
+    }
+}
+
 /// `PidFd` for async and efficient method of reaping children process and
 /// race-free signal sending.
 ///
@@ -50,8 +431,8 @@ fn waitid(idtype: libc::idtype_t, id: libc::id_t, options: c_int)
 ///         let exitinfo = pidfd.waitpid().await.unwrap();
 ///
 ///         match exitinfo.get_code() {
-///             ExitCode::Killed(_) => panic!("Children killed by signal!"),
 ///             ExitCode::Exited(code) => assert_eq!(code, 0),
+///             code => panic!("Children killed by signal: {:#?}", code),
 ///         }
 ///     }
 /// }
@@ -59,7 +440,13 @@ fn waitid(idtype: libc::idtype_t, id: libc::id_t, options: c_int)
 /// f();
 /// ```
 pub struct PidFd {
-    inner: Fd
+    /// Registration of `inner` with tokio, created lazily on the first wait,
+    /// along with the pid of the process that created it.
+    ///
+    /// Declared before `inner` so that it is deregistered before the fd is
+    /// closed and its number possibly reused.
+    registration: OnceCell<(AsyncFd<RawFd>, pid_t)>,
+    inner: Fd,
 }
 impl PidFd {
     /// # Creating `PidFd` from the pid of children
@@ -74,15 +461,41 @@ impl PidFd {
     ///
     /// If any of these conditions does not hold, then the child process
     /// (along with a PID file descriptor that refers to it) should instead be created
-    /// using `clone` with the `CLONE_PIDFD` flag and uses the `from_raw` function to
-    /// create `PidFd`.
+    /// using `clone_with_pidfd`, or `clone` with the `CLONE_PIDFD` flag and uses the
+    /// `from_raw` function to create `PidFd`.
     ///
     /// # Creating `PidFd` from arbitary pid
     ///
     /// Make sure to verify that the process pointed to by this pid is the one you
     /// want.
     pub fn open(pid: pid_t) -> Result<Self> {
-        let flags: c_uint = 0;
+        Self::open_with_flags(pid, PidFdFlags::empty())
+    }
+
+    /// Same as `PidFd::open`, but with `flags`.
+    ///
+    /// Kernels older than 5.10 fail with `EINVAL` on `PidFdFlags::NONBLOCK`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::fork;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid != 0 { // parent
+    ///         let pidfd = PidFd::open_with_flags(pid, PidFdFlags::NONBLOCK).unwrap();
+    ///         pidfd.waitpid().await.unwrap();
+    ///     }
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn open_with_flags(pid: pid_t, flags: PidFdFlags) -> Result<Self> {
+        let flags: c_uint = flags.bits();
         let ret = unsafe {
             syscall(libc::SYS_pidfd_open, pid, flags)
         };
@@ -93,12 +506,148 @@ impl PidFd {
         }
     }
 
+    /// Create `PidFd` from a child spawned by `std::process::Command`.
+    ///
+    /// As long as `child` has not been waited on, it stays a zombie after exit and
+    /// its pid cannot be reused, so this is race-free under the same conditions as
+    /// listed in `PidFd::open`.
+    ///
+    /// However, if `SIGCHLD` is ignored or the child can be reaped elsewhere, the pid
+    /// may be reused before `pidfd_open` is called and the returned `PidFd` may refer
+    /// to an unrelated process.
+    /// In that case, create the child using `clone_with_pidfd` instead.
+    ///
+    /// Once the child is reaped via `PidFd`, `child` must not be waited on again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::process::Command;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let child = Command::new("/bin/true").spawn().unwrap();
+    ///     let pidfd = PidFd::from_child(&child).unwrap();
+    ///
+    ///     let exit_info = pidfd.waitpid().await.unwrap();
+    ///     assert!(matches!(exit_info.get_code(), ExitCode::Exited(0)));
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn from_child(child: &std::process::Child) -> Result<Self> {
+        Self::open(child.id() as pid_t)
+    }
+
     /// # Safety
     ///
     /// Make sure `fd` is actually created via `clone` with the `CLONE_PIDFD` flag or
     /// by using `pidfd_open`.
     pub const unsafe fn from_raw(fd: RawFd) -> Self {
-        Self { inner: Fd::new(fd) }
+        Self {
+            registration: OnceCell::const_new(),
+            inner: Fd::new(fd),
+        }
+    }
+
+    async fn registration(&self) -> Result<&AsyncFd<RawFd>> {
+        let pidfd = self.inner.as_raw_fd();
+
+        let (registration, _pid) = self.registration.get_or_try_init(|| async move {
+            let registration = AsyncFd::with_interest(pidfd, Interest::READABLE)?;
+            Ok::<_, Error>((registration, unsafe { libc::getpid() }))
+        }).await?;
+
+        Ok(registration)
+    }
+
+    /// Re-create the tokio registration of this `PidFd` on the current runtime.
+    ///
+    /// The pidfd itself stays valid after `fork`, but the registration cached
+    /// by the first wait belongs to the runtime it was created on and may be
+    /// stale in the child.
+    ///
+    /// This is an escape hatch for complex process topologies: call it from
+    /// within the runtime that should drive this `PidFd` from now on.
+    ///
+    /// If the stale registration was created by another process, i.e. before
+    /// `fork`, it is leaked instead of deregistered, since the epoll instance
+    /// it was registered with is shared with that process.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use libc::{fork, pause, _exit};
+    /// use tokio::runtime::Builder;
+    /// use tokio::time::timeout;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+    ///
+    /// let pid = unsafe { fork() };
+    /// assert!(pid >= 0);
+    /// if pid == 0 { // child
+    ///     loop {
+    ///         unsafe { pause() };
+    ///     }
+    /// }
+    ///
+    /// let mut pidfd = PidFd::open(pid).unwrap();
+    ///
+    /// // Register the pidfd with `runtime` by waiting on it.
+    /// runtime.block_on(async {
+    ///     let wait = timeout(Duration::from_millis(10), pidfd.wait_for_terminate());
+    ///     assert!(wait.await.is_err());
+    /// });
+    ///
+    /// let refresher = unsafe { fork() };
+    /// assert!(refresher >= 0);
+    /// if refresher == 0 { // child
+    ///     let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+    ///     runtime.block_on(async {
+    ///         pidfd.refresh().unwrap();
+    ///
+    ///         let wait = timeout(Duration::from_millis(10), pidfd.wait_for_terminate());
+    ///         assert!(wait.await.is_err());
+    ///     });
+    ///     unsafe { _exit(0) };
+    /// }
+    ///
+    /// runtime.block_on(async {
+    ///     let refresher = PidFd::open(refresher).unwrap().waitpid().await.unwrap();
+    ///     assert!(refresher.success());
+    ///
+    ///     // The registration of the parent still works after the refresh in
+    ///     // the child.
+    ///     pidfd.send_signal(Signal::Sigkill, None).unwrap();
+    ///     let wait = timeout(Duration::from_secs(5), pidfd.wait_for_terminate());
+    ///     wait.await.unwrap().unwrap();
+    ///
+    ///     pidfd.waitpid().await.unwrap();
+    /// });
+    /// ```
+    pub fn refresh(&mut self) -> Result<()> {
+        if let Some((registration, pid)) = self.registration.take() {
+            if pid == unsafe { libc::getpid() } {
+                // Deregister the old one first, otherwise epoll rejects the
+                // fd as already registered.
+                drop(registration);
+            } else {
+                // Deregistering would remove the fd from the epoll instance
+                // shared with the process that created it.
+                std::mem::forget(registration);
+            }
+        }
+
+        let pidfd = self.inner.as_raw_fd();
+        let registration = AsyncFd::with_interest(pidfd, Interest::READABLE)?;
+
+        // `&mut self` guarantees nobody has initialized it in the meantime.
+        self.registration.set((registration, unsafe { libc::getpid() })).unwrap();
+
+        Ok(())
     }
 
     /// * `self` - The calling process must either be in the same PID namespace
@@ -112,59 +661,948 @@ impl PidFd {
     ///    - `si_code` is set to `SI_USER`;
     ///    - `si_pid` is set to the caller's PID;
     ///    - `si_uid` is set to the caller's real user ID.
+    ///
+    /// If `info` is rejected by the kernel, the returned error carries a
+    /// `SigInfoError` describing why, which can be retrieved via
+    /// `Error::get_ref` and `downcast_ref`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{fork, pause, siginfo_t, SI_USER};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let pid = unsafe { fork() };
+    /// assert!(pid >= 0);
+    /// if pid == 0 { // child
+    ///     unsafe { pause() };
+    ///     return;
+    /// }
+    ///
+    /// let pidfd = PidFd::open(pid).unwrap();
+    ///
+    /// let mut info: siginfo_t = unsafe { std::mem::zeroed() };
+    /// info.si_signo = Signal::Sigterm.into();
+    /// info.si_code = SI_USER;
+    ///
+    /// let err = pidfd.send_signal(Signal::Sigterm, Some(&info)).unwrap_err();
+    /// assert_eq!(
+    ///     err.get_ref().unwrap().downcast_ref::<SigInfoError>(),
+    ///     Some(&SigInfoError::CodeNotPermitted(SI_USER))
+    /// );
+    ///
+    /// pidfd.send_signal(Signal::Sigkill, None).unwrap();
+    /// ```
     pub fn send_signal(&self, signal: Signal, info: Option<&siginfo_t>) -> Result<()> {
-        let flags: libc::c_uint = 0;
+        self.send_signal_with_flags(signal, info, SendSignalFlags::empty())
+    }
+
+    /// Same as `PidFd::send_signal`, but with `flags` to choose whether the
+    /// signal is directed at the thread, thread group or process group.
+    ///
+    /// At most one flag can be specified.
+    ///
+    /// Kernels older than 6.9 fail with `EINVAL` on any non-empty `flags`,
+    /// in which case `PidFd::send_signal` is the closest fallback.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{fork, pause, EINVAL};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid == 0 { // child
+    ///         loop {
+    ///             unsafe { pause() };
+    ///         }
+    ///     }
+    ///
+    ///     let pidfd = PidFd::open(pid).unwrap();
+    ///
+    ///     pidfd.send_signal_with_flags(Signal::Sigstop, None, SendSignalFlags::empty())
+    ///         .unwrap();
+    ///     pidfd.wait_for_state(WaitOptions::STOPPED).await.unwrap();
+    ///
+    ///     match pidfd.send_signal_with_flags(Signal::Sigkill, None, SendSignalFlags::THREAD) {
+    ///         Err(err) if err.raw_os_error() == Some(EINVAL) => { // kernel older than 6.9
+    ///             pidfd.send_signal(Signal::Sigkill, None).unwrap()
+    ///         },
+    ///         result => result.unwrap(),
+    ///     }
+    ///
+    ///     match pidfd.waitpid().await.unwrap().get_code() {
+    ///         ExitCode::Killed(signal) => assert_eq!(signal, Signal::Sigkill),
+    ///         code => panic!("Unexpected exit code {:#?}", code),
+    ///     }
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn send_signal_with_flags(
+        &self,
+        signal: Signal,
+        info: Option<&siginfo_t>,
+        flags: SendSignalFlags
+    ) -> Result<()> {
+        let flags: c_uint = flags.bits();
 
         let pidfd = self.inner.as_raw_fd();
         let sig: c_int = signal.into();
-        let info = info.map_or(null(), |info_ref| info_ref as *const _);
+
+        if let Some(info) = info {
+            if info.si_signo != sig {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    SigInfoError::SignoMismatch { signal, si_signo: info.si_signo }
+                ));
+            }
+        }
+
+        let info_ptr = info.map_or(null(), |info_ref| info_ref as *const _);
 
         let ret = unsafe {
-            syscall(libc::SYS_pidfd_send_signal, pidfd, sig, info, flags)
+            syscall(libc::SYS_pidfd_send_signal, pidfd, sig, info_ptr, flags)
         };
         if ret < 0 {
-            Err(Error::last_os_error())
+            let err = Error::last_os_error();
+
+            match info {
+                // The kernel only permits sending these si_code to the caller itself.
+                Some(info) if err.raw_os_error() == Some(libc::EPERM) &&
+                    (info.si_code >= 0 || info.si_code == libc::SI_TKILL) =>
+                {
+                    Err(Error::new(
+                        ErrorKind::PermissionDenied,
+                        SigInfoError::CodeNotPermitted(info.si_code)
+                    ))
+                },
+                _ => Err(err),
+            }
         } else {
             Ok(())
         }
     }
 
-    /// Asynchronously wait for the process to terminate.
-    pub async fn wait_for_terminate(&self) -> Result<()> {
-        let pidfd = self.inner.as_raw_fd();
-        let pidfd = AsyncFd::with_interest(pidfd, Interest::READABLE)?;
-
-        pidfd.readable().await?.retain_ready();
-
-        Ok(())
-    }
-
-    /// Asynchronously wait for the child process to terminate and reap it
-    /// using `waitid`.
-    pub async fn waitpid(&self) -> Result<ExitInfo> {
-        self.wait_for_terminate().await?;
-
-        let waitid_option = libc::WEXITED | libc::WNOHANG;
+    /// Returns the pid of the process referred to by `self`, as reported by the
+    /// `Pid:` field of `/proc/self/fdinfo/<fd>`.
+    ///
+    /// Fails with `ESRCH` if the process has been reaped, or if it is not in
+    /// the pid namespace of `/proc`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::process::Command;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let child = Command::new("/bin/true").spawn().unwrap();
+    ///     let pidfd = PidFd::from_child(&child).unwrap();
+    ///
+    ///     assert_eq!(pidfd.pid().unwrap(), child.id() as pid_t);
+    ///
+    ///     pidfd.waitpid().await.unwrap();
+    ///     assert_eq!(pidfd.pid().unwrap_err().raw_os_error(), Some(libc::ESRCH));
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn pid(&self) -> Result<pid_t> {
+        let path = format!("/proc/self/fdinfo/{}", self.inner.as_raw_fd());
+        let fdinfo = std::fs::read_to_string(path)?;
 
-        let pidfd = self.inner.as_raw_fd();
-        let siginfo = waitid(libc::P_PIDFD, pidfd as u32, waitid_option)?.unwrap();
+        let pid = fdinfo
+            .lines()
+            .find_map(|line| line.strip_prefix("Pid:"))
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "no Pid field in fdinfo"))?
+            .trim()
+            .parse::<pid_t>()
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
 
-        Ok(unsafe { ExitInfo::new(siginfo) })
+        // -1 means the process has been reaped and 0 means it is in another
+        // pid namespace.
+        if pid <= 0 {
+            Err(Error::from_raw_os_error(libc::ESRCH))
+        } else {
+            Ok(pid)
+        }
     }
-}
 
+    /// Duplicate the file descriptor `target_fd` of the process referred to by
+    /// `self` into the calling process.
+    ///
+    /// The returned fd is close-on-exec.
+    ///
+    /// The calling process must have `PTRACE_MODE_ATTACH_REALCREDS` permission
+    /// over the target process (e.g. having `CAP_SYS_PTRACE` or being its
+    /// ancestor under the default Yama settings), otherwise it fails with `EPERM`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::os::unix::io::RawFd;
+    /// use libc::{fork, pause, pipe, read, write, c_void};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut chan: [RawFd; 2] = [-1, -1];
+    /// assert_eq!(0, unsafe { pipe(chan.as_mut_ptr()) });
+    ///
+    /// let pid = unsafe { fork() };
+    /// assert!(pid >= 0);
+    /// if pid == 0 { // child
+    ///     let mut fds: [RawFd; 2] = [-1, -1];
+    ///     assert_eq!(0, unsafe { pipe(fds.as_mut_ptr()) });
+    ///
+    ///     unsafe {
+    ///         write(fds[1], b"hello".as_ptr() as *const c_void, 5);
+    ///         write(chan[1], fds.as_ptr() as *const c_void, 4);
+    ///
+    ///         pause();
+    ///     }
+    /// }
+    ///
+    /// let pidfd = PidFd::open(pid).unwrap();
+    ///
+    /// let mut target_fd: RawFd = -1;
+    /// let cnt = unsafe { read(chan[0], &mut target_fd as *mut _ as *mut c_void, 4) };
+    /// assert_eq!(cnt, 4);
+    ///
+    /// let fd = pidfd.get_fd(target_fd).unwrap();
+    /// let mut buf = [0; 5];
+    /// assert_eq!(fd.read(&mut buf).unwrap(), 5);
+    /// assert_eq!(&buf, b"hello");
+    ///
+    /// pidfd.send_signal(Signal::Sigkill, None).unwrap();
+    /// ```
+    pub fn get_fd(&self, target_fd: RawFd) -> Result<Fd> {
+        let flags: c_uint = 0;
+
+        let pidfd = self.inner.as_raw_fd();
+
+        let ret = unsafe {
+            syscall(libc::SYS_pidfd_getfd, pidfd, target_fd, flags)
+        };
+        if ret < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(unsafe { Fd::new(ret as RawFd) })
+        }
+    }
+
+    /// Same as `Fd::close`.
+    ///
+    /// Like dropping it, this does not reap the child.
+    pub fn close(self) -> Result<()> {
+        let PidFd { inner, registration } = self;
+
+        // Deregister from tokio before closing the fd.
+        drop(registration);
+
+        inner.close()
+    }
+
+    /// Convert into an `OwnedPidFd` that reaps the child on drop.
+    pub fn into_owning(self) -> OwnedPidFd {
+        OwnedPidFd { inner: self }
+    }
+
+    /// Test if the process has not terminated yet, without blocking or
+    /// reaping it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{fork, pause};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid == 0 { // child
+    ///         loop {
+    ///             unsafe { pause() };
+    ///         }
+    ///     }
+    ///
+    ///     let pidfd = PidFd::open(pid).unwrap();
+    ///     assert!(pidfd.is_alive().unwrap());
+    ///
+    ///     pidfd.send_signal(Signal::Sigkill, None).unwrap();
+    ///     pidfd.wait_for_terminate().await.unwrap();
+    ///     assert!(!pidfd.is_alive().unwrap());
+    ///
+    ///     pidfd.waitpid().await.unwrap();
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn is_alive(&self) -> Result<bool> {
+        // The pidfd becomes readable once the process terminates.
+        Ok(!self.inner.is_readable()?)
+    }
+
+    /// Asynchronously wait for the process to terminate.
+    pub async fn wait_for_terminate(&self) -> Result<()> {
+        self.registration().await?.readable().await?.retain_ready();
+
+        Ok(())
+    }
+
+    /// Asynchronously wait for the process to terminate for at most `dur`.
+    ///
+    /// Returns `true` if the process terminated within `dur`, `false` on timeout.
+    ///
+    /// The `PidFd` stays usable after a timeout, e.g. for a subsequent `waitpid`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use libc::{fork, pause};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid == 0 { // child
+    ///         unsafe { pause() };
+    ///         return;
+    ///     }
+    ///
+    ///     let pidfd = PidFd::open(pid).unwrap();
+    ///     let dur = Duration::from_millis(50);
+    ///
+    ///     assert!(!pidfd.wait_for_terminate_timeout(dur).await.unwrap());
+    ///
+    ///     pidfd.send_signal(Signal::Sigkill, None).unwrap();
+    ///     assert!(pidfd.wait_for_terminate_timeout(dur * 100).await.unwrap());
+    ///
+    ///     pidfd.waitpid().await.unwrap();
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn wait_for_terminate_timeout(&self, dur: Duration) -> Result<bool> {
+        match tokio::time::timeout(dur, self.wait_for_terminate()).await {
+            Ok(result) => result.map(|_| true),
+            Err(_elapsed) => Ok(false),
+        }
+    }
+
+    /// Asynchronously wait for the child process to change into one of the
+    /// states specified in `opts`.
+    ///
+    /// If the child terminates and `WaitOptions::EXITED` is specified, it is
+    /// reaped unless `WaitOptions::NOWAIT` is also specified.
+    ///
+    /// Each stop or continue is reported only once, unless `WaitOptions::NOWAIT`
+    /// is specified, in which case the same state change is reported again by
+    /// the next call until it is consumed by a call without `NOWAIT`.
+    ///
+    /// Stop and continue do not make the pidfd readable, so they are detected
+    /// by polling every 10ms.
+    ///
+    /// If the child terminates while `WaitOptions::EXITED` is not specified,
+    /// returns an error of `ECHILD`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{fork, kill, pause, SIGCONT, SIGSTOP};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid == 0 { // child
+    ///         loop {
+    ///             unsafe { pause() };
+    ///         }
+    ///     }
+    ///
+    ///     let pidfd = PidFd::open(pid).unwrap();
+    ///
+    ///     assert_eq!(0, unsafe { kill(pid, SIGSTOP) });
+    ///     match pidfd.wait_for_state(WaitOptions::STOPPED).await.unwrap() {
+    ///         StateChange::Stopped(signal) => assert_eq!(signal, Signal::Sigstop),
+    ///         state => panic!("Unexpected state {:#?}", state),
+    ///     }
+    ///
+    ///     assert_eq!(0, unsafe { kill(pid, SIGCONT) });
+    ///     match pidfd.wait_for_state(WaitOptions::CONTINUED).await.unwrap() {
+    ///         StateChange::Continued => (),
+    ///         state => panic!("Unexpected state {:#?}", state),
+    ///     }
+    ///
+    ///     pidfd.send_signal(Signal::Sigkill, None).unwrap();
+    ///     pidfd.waitpid().await.unwrap();
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn wait_for_state(&self, opts: WaitOptions) -> Result<StateChange> {
+        let waitid_option = opts.bits() | libc::WNOHANG;
+
+        let pidfd = self.inner.as_raw_fd();
+
+        loop {
+            if let Some(siginfo) = waitid(libc::P_PIDFD, pidfd as u32, waitid_option)? {
+                break Ok(unsafe { StateChange::new(siginfo) });
+            }
+
+            if !opts.intersects(WaitOptions::STOPPED | WaitOptions::CONTINUED) {
+                self.wait_for_terminate().await?;
+            } else if self.wait_for_terminate_timeout(STATE_POLL_INTERVAL).await? &&
+                !opts.contains(WaitOptions::EXITED)
+            {
+                // Check once more in case it changed state right before exiting.
+                break match waitid(libc::P_PIDFD, pidfd as u32, waitid_option)? {
+                    Some(siginfo) => Ok(unsafe { StateChange::new(siginfo) }),
+                    None => Err(Error::from_raw_os_error(libc::ECHILD)),
+                };
+            }
+        }
+    }
+
+    /// Asynchronously wait for the child process to terminate and reap it by
+    /// polling `waitid`, with exponential backoff from `initial` up to `max`
+    /// between attempts.
+    ///
+    /// `max` is raised to at least 1ms and `initial` is clamped between 1ms
+    /// and `max`, so that it never busy-polls `waitid`.
+    ///
+    /// This is a fallback for pidfds that never become readable, e.g. on
+    /// kernels not supporting polling pidfd, and is less efficient than
+    /// `PidFd::waitpid`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use libc::fork;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid != 0 { // parent
+    ///         let pidfd = PidFd::open(pid).unwrap();
+    ///         let exitinfo = pidfd.wait_polling(
+    ///             Duration::from_millis(1),
+    ///             Duration::from_millis(16)
+    ///         ).await.unwrap();
+    ///
+    ///         match exitinfo.get_code() {
+    ///             ExitCode::Exited(code) => assert_eq!(code, 0),
+    ///             code => panic!("Children killed by signal: {:#?}", code),
+    ///         }
+    ///
+    ///         // Zero delays are clamped rather than busy-polling.
+    ///         let pid = unsafe { fork() };
+    ///         assert!(pid >= 0);
+    ///         if pid == 0 { // child
+    ///             unsafe { libc::_exit(0) };
+    ///         }
+    ///
+    ///         let pidfd = PidFd::open(pid).unwrap();
+    ///         let exitinfo = pidfd.wait_polling(Duration::ZERO, Duration::ZERO)
+    ///             .await
+    ///             .unwrap();
+    ///         assert!(exitinfo.success());
+    ///     }
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn wait_polling(&self, initial: Duration, max: Duration) -> Result<ExitInfo> {
+        let waitid_option = libc::WEXITED | libc::WNOHANG;
+
+        let pidfd = self.inner.as_raw_fd();
+        let max = max.max(MIN_POLL_DELAY);
+        let mut delay = initial.clamp(MIN_POLL_DELAY, max);
+
+        loop {
+            if let Some(siginfo) = waitid(libc::P_PIDFD, pidfd as u32, waitid_option)? {
+                break Ok(unsafe { ExitInfo::new(siginfo) });
+            }
+
+            tokio::time::sleep(delay).await;
+            delay = delay.saturating_mul(2).min(max);
+        }
+    }
+
+    /// Asynchronously wait for the child process to terminate and reap it
+    /// using `waitid`.
+    pub async fn waitpid(&self) -> Result<ExitInfo> {
+        self.wait_for_exit(libc::WEXITED | libc::WNOHANG).await
+    }
+
+    /// Asynchronously wait for the child process to terminate and return its
+    /// `ExitInfo` without reaping it.
+    ///
+    /// The child stays a zombie until it is reaped, e.g. by a later `waitpid`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{fork, _exit, ECHILD};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid == 0 { // child
+    ///         unsafe { _exit(3) };
+    ///     }
+    ///
+    ///     let pidfd = PidFd::open(pid).unwrap();
+    ///
+    ///     let peeked = pidfd.peek().await.unwrap();
+    ///     assert_eq!(peeked.get_code(), ExitCode::Exited(3));
+    ///     assert_eq!(pidfd.peek().await.unwrap(), peeked);
+    ///
+    ///     assert_eq!(pidfd.waitpid().await.unwrap(), peeked);
+    ///
+    ///     let err = reap(IdType::Pid(pid), WaitOptions::EXITED).unwrap_err();
+    ///     assert_eq!(err.raw_os_error(), Some(ECHILD));
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn peek(&self) -> Result<ExitInfo> {
+        self.wait_for_exit(libc::WEXITED | libc::WNOWAIT | libc::WNOHANG).await
+    }
+
+    async fn wait_for_exit(&self, waitid_option: c_int) -> Result<ExitInfo> {
+        self.wait_for_terminate().await?;
+
+        let pidfd = self.inner.as_raw_fd();
+        let siginfo = waitid(libc::P_PIDFD, pidfd as u32, waitid_option)?.unwrap();
+
+        Ok(unsafe { ExitInfo::new(siginfo) })
+    }
+}
+
+/// Result of `clone_with_pidfd`.
+pub enum ForkResult {
+    /// Returned in the parent.
+    Parent {
+        /// pid of the child.
+        pid: pid_t,
+        /// `PidFd` referring to the child.
+        pidfd: PidFd,
+    },
+    /// Returned in the child.
+    Child,
+}
+
+/// `struct clone_args` of `clone3`, up to `CLONE_ARGS_SIZE_VER0`.
+#[repr(C)]
+struct CloneArgs {
+    flags: u64,
+    pidfd: u64,
+    child_tid: u64,
+    parent_tid: u64,
+    exit_signal: u64,
+    stack: u64,
+    stack_size: u64,
+    tls: u64,
+}
+
+/// Create a child process like `fork`, along with a `PidFd` referring to it,
+/// using `clone3` with `CLONE_PIDFD`.
+///
+/// Unlike `PidFd::open`, the `PidFd` is created atomically with the child, so
+/// it always refers to the child even if the child is reaped elsewhere.
+///
+/// Requires Linux 5.3.
+///
+/// # Safety
+///
+/// Same as `fork`: if the calling process is multi-threaded, the child must only
+/// call async-signal-safe functions until it calls `execve` or `_exit`.
+///
+/// In addition, `pthread_atfork` handlers are not run, so this holds even if
+/// the calling process is single-threaded, as locks held by libc (e.g. of
+/// `malloc`) are not reset in the child.
+///
+/// The child must not use the tokio runtime or any `PidFd`, `SignalFd` and other
+/// fds registered in it.
+///
+/// # Example
+///
+/// ```
+/// use libc::_exit;
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     match unsafe { clone_with_pidfd() }.unwrap() {
+///         ForkResult::Child => unsafe { _exit(7) },
+///         ForkResult::Parent { pid, pidfd } => {
+///             let exit_info = pidfd.waitpid().await.unwrap();
+///             assert_eq!(exit_info.get_pid(), pid);
+///             assert_eq!(exit_info.get_code(), ExitCode::Exited(7));
+///         },
+///     }
+/// }
+///
+/// f();
+/// ```
+pub unsafe fn clone_with_pidfd() -> Result<ForkResult> {
+    let mut pidfd: RawFd = -1;
+
+    let mut args = CloneArgs {
+        flags: libc::CLONE_PIDFD as u64,
+        pidfd: &mut pidfd as *mut RawFd as u64,
+        child_tid: 0,
+        parent_tid: 0,
+        exit_signal: libc::SIGCHLD as u64,
+        stack: 0,
+        stack_size: 0,
+        tls: 0,
+    };
+
+    let ret = syscall(
+        libc::SYS_clone3,
+        &mut args as *mut CloneArgs,
+        std::mem::size_of::<CloneArgs>()
+    );
+    match ret {
+        ret if ret < 0 => Err(Error::last_os_error()),
+        0 => Ok(ForkResult::Child),
+        pid => Ok(ForkResult::Parent {
+            pid: pid as pid_t,
+            pidfd: PidFd::from_raw(pidfd),
+        }),
+    }
+}
+
+/// `PidFd` of a child that, on drop, reaps the child if it has already
+/// terminated, so that it does not stay a zombie.
+///
+/// Reaping only works if the calling process is the parent of the process,
+/// otherwise the drop does nothing.
+///
+/// # Example
+///
+/// ```
+/// use libc::{fork, waitid, siginfo_t, P_PID, WEXITED, WNOHANG, ECHILD};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let pid = unsafe { fork() };
+///     assert!(pid >= 0);
+///     if pid != 0 { // parent
+///         let pidfd = PidFd::open(pid).unwrap().into_owning();
+///         pidfd.wait_for_terminate().await.unwrap();
+///         drop(pidfd);
+///
+///         let mut siginfo: siginfo_t = unsafe { std::mem::zeroed() };
+///         let ret = unsafe {
+///             waitid(P_PID, pid as u32, &mut siginfo, WEXITED | WNOHANG)
+///         };
+///         assert_eq!(ret, -1);
+///         assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(ECHILD));
+///     }
+/// }
+///
+/// f();
+/// ```
+pub struct OwnedPidFd {
+    inner: PidFd,
+}
+impl OwnedPidFd {
+    /// Convert back into `PidFd` without reaping.
+    pub fn into_inner(self) -> PidFd {
+        let this = std::mem::ManuallyDrop::new(self);
+        unsafe { std::ptr::read(&this.inner) }
+    }
+}
+impl std::ops::Deref for OwnedPidFd {
+    type Target = PidFd;
+
+    fn deref(&self) -> &PidFd {
+        &self.inner
+    }
+}
+impl Drop for OwnedPidFd {
+    fn drop(&mut self) {
+        let waitid_option = libc::WEXITED | libc::WNOHANG;
+
+        let pidfd = self.inner.as_raw_fd();
+
+        // Best effort: the process might still be running or not be our child.
+        let _ = waitid(libc::P_PIDFD, pidfd as u32, waitid_option);
+    }
+}
+
+/// Reason of `PidFd::send_signal` rejecting the `siginfo_t` passed to it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SigInfoError {
+    /// `si_signo` does not match the signal being sent.
+    SignoMismatch { signal: Signal, si_signo: c_int },
+    /// `si_code` is not permitted when sending to another process.
+    ///
+    /// Only negative `si_code` other than `SI_TKILL`, e.g. `SI_QUEUE`, is
+    /// permitted, so that the receiver cannot be fooled into believing that
+    /// the signal was sent by `kill` or the kernel.
+    CodeNotPermitted(c_int),
+}
+impl fmt::Display for SigInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigInfoError::SignoMismatch { signal, si_signo } => write!(
+                f, "si_signo {} does not match the signal {:?} being sent", si_signo, signal
+            ),
+            SigInfoError::CodeNotPermitted(si_code) => write!(
+                f,
+                "si_code {} is not permitted when sending to another process, \
+                 only negative si_code other than SI_TKILL is",
+                si_code
+            ),
+        }
+    }
+}
+impl std::error::Error for SigInfoError {}
+
+impl AsRawFd for PidFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+impl IntoRawFd for PidFd {
+    fn into_raw_fd(self) -> RawFd {
+        let PidFd { inner, registration } = self;
+
+        // Deregister from tokio before giving away the fd.
+        drop(registration);
+
+        inner.into_raw_fd()
+    }
+}
+impl From<PidFd> for OwnedFd {
+    fn from(pidfd: PidFd) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(pidfd.into_raw_fd()) }
+    }
+}
+/// `fd` must be a pidfd, created via `clone` with the `CLONE_PIDFD` flag or by
+/// using `pidfd_open`.
+///
+/// # Example
+///
+/// ```
+/// use std::fs::File;
+/// use std::os::unix::io::{FromRawFd, IntoRawFd, OwnedFd};
+/// use libc::fork;
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let pid = unsafe { fork() };
+///     assert!(pid >= 0);
+///     if pid != 0 { // parent
+///         let pidfd: OwnedFd = PidFd::open(pid).unwrap().into();
+///         let pidfd = PidFd::from(pidfd);
+///
+///         pidfd.waitpid().await.unwrap();
+///
+///         // `File` is now the only owner of the fd.
+///         drop(unsafe { File::from_raw_fd(pidfd.into_raw_fd()) });
+///     }
+/// }
+///
+/// f();
+/// ```
+impl From<OwnedFd> for PidFd {
+    fn from(fd: OwnedFd) -> PidFd {
+        unsafe { PidFd::from_raw(fd.into_raw_fd()) }
+    }
+}
+
+/// State change of a child reported by `waitid`.
+///
+/// # Example
+///
+/// ```
+/// use std::ptr::null_mut;
+/// use libc::{fork, getpid, ptrace, raise, _exit, SIGSTOP, SIGTRAP};
+/// use libc::{PTRACE_O_TRACESYSGOOD, PTRACE_SETOPTIONS, PTRACE_SYSCALL, PTRACE_TRACEME};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let pid = unsafe { fork() };
+///     assert!(pid >= 0);
+///     if pid == 0 { // child
+///         unsafe {
+///             ptrace(PTRACE_TRACEME, 0, null_mut::<u8>(), null_mut::<u8>());
+///             raise(SIGSTOP);
+///             getpid();
+///             _exit(0);
+///         }
+///     }
+///
+///     let pidfd = PidFd::open(pid).unwrap();
+///
+///     match pidfd.wait_for_state(WaitOptions::STOPPED).await.unwrap() {
+///         StateChange::Stopped(signal) => assert_eq!(signal, Signal::Sigstop),
+///         state => panic!("Unexpected state {:#?}", state),
+///     }
+///
+///     // Stop at the next syscall, which is reported as `SIGTRAP | 0x80`.
+///     unsafe {
+///         let options = PTRACE_O_TRACESYSGOOD as usize as *mut u8;
+///         assert_eq!(ptrace(PTRACE_SETOPTIONS, pid, null_mut::<u8>(), options), 0);
+///         assert_eq!(ptrace(PTRACE_SYSCALL, pid, null_mut::<u8>(), null_mut::<u8>()), 0);
+///     }
+///
+///     match pidfd.wait_for_state(WaitOptions::STOPPED).await.unwrap() {
+///         StateChange::StoppedRaw(status) => assert_eq!(status, SIGTRAP | 0x80),
+///         state => panic!("Unexpected state {:#?}", state),
+///     }
+///
+///     pidfd.send_signal(Signal::Sigkill, None).unwrap();
+///     pidfd.waitpid().await.unwrap();
+/// }
+///
+/// f();
+/// ```
 #[derive(Copy, Clone, Debug)]
+pub enum StateChange {
+    /// The process has terminated.
+    Exited(ExitInfo),
+    /// The process has been stopped (or trapped) by the signal.
+    Stopped(Signal),
+    /// The process has been stopped (or trapped) with a status that `Signal`
+    /// cannot represent, e.g. `SIGTRAP | 0x80` for syscall stops under
+    /// `PTRACE_O_TRACESYSGOOD` or `SIGTRAP | event << 8` for ptrace event stops.
+    StoppedRaw(c_int),
+    /// The process has been resumed by `SIGCONT`.
+    Continued,
+}
+impl StateChange {
+    /// # Safety
+    ///
+    /// * `siginfo` - Must be retrieved via `waitid`.
+    pub unsafe fn new(siginfo: siginfo_t) -> StateChange {
+        match siginfo.si_code {
+            libc::CLD_STOPPED | libc::CLD_TRAPPED => {
+                let status = siginfo.si_status();
+
+                Signal::try_from(status)
+                    .map(StateChange::Stopped)
+                    .unwrap_or(StateChange::StoppedRaw(status))
+            },
+            libc::CLD_CONTINUED => StateChange::Continued,
+            _ => StateChange::Exited(ExitInfo::new(siginfo)),
+        }
+    }
+}
+
+/// With feature `serde`, it is serialized as an externally tagged enum,
+/// e.g. `{"Exited":1}` or `{"Killed":"SIGKILL"}`.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use async_linux_spec_fd::*;
+///
+/// let json = serde_json::to_string(&ExitCode::Exited(1)).unwrap();
+/// assert_eq!(json, r#"{"Exited":1}"#);
+/// assert!(matches!(serde_json::from_str(&json).unwrap(), ExitCode::Exited(1)));
+///
+/// let json = serde_json::to_string(&ExitCode::Killed(Signal::Sigkill)).unwrap();
+/// assert_eq!(json, r#"{"Killed":"SIGKILL"}"#);
+/// assert!(matches!(
+///     serde_json::from_str(&json).unwrap(),
+///     ExitCode::Killed(Signal::Sigkill)
+/// ));
+///
+/// let json = serde_json::to_string(&ExitCode::KilledRaw(32)).unwrap();
+/// assert_eq!(json, r#"{"KilledRaw":32}"#);
+/// assert_eq!(serde_json::from_str::<ExitCode>(&json).unwrap(), ExitCode::KilledRaw(32));
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExitCode {
+    /// The child is killed by the signal.
     Killed(Signal),
+    /// The child is killed by a signal that `Signal` cannot represent,
+    /// e.g. one reserved by libc for internal use.
+    KilledRaw(c_int),
+    /// The child exits with the exit code, which is already decoded
+    /// (what `WEXITSTATUS` gives for `wait(2)`), thus always in 0..=255.
     Exited(c_int),
 }
 
-#[derive(Copy, Clone, Debug)]
+/// Information about a terminated child.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use libc::{fork, _exit};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let pid = unsafe { fork() };
+///     assert!(pid >= 0);
+///     if pid == 0 { // child
+///         unsafe { _exit(2) };
+///     }
+///
+///     let pidfd = PidFd::open(pid).unwrap();
+///
+///     let peeked = match pidfd.wait_for_state(WaitOptions::EXITED | WaitOptions::NOWAIT)
+///         .await
+///         .unwrap()
+///     {
+///         StateChange::Exited(exit_info) => exit_info,
+///         state => panic!("Unexpected state {:#?}", state),
+///     };
+///     let reaped = pidfd.waitpid().await.unwrap();
+///
+///     assert_eq!(peeked, reaped);
+///     assert_eq!(reaped.get_pid(), pid);
+///     assert_eq!(reaped.get_code(), ExitCode::Exited(2));
+///
+///     # #[cfg(feature = "serde")] {
+///     let json = serde_json::to_string(&reaped).unwrap();
+///     assert_eq!(serde_json::from_str::<ExitInfo>(&json).unwrap(), reaped);
+///     # }
+///
+///     let set: HashSet<_> = [peeked, reaped].iter().copied().collect();
+///     assert_eq!(set.len(), 1);
+/// }
+///
+/// f();
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExitInfo {
+    /// pid of the child
+    pid: pid_t,
     /// uid of the child when it exits
     uid: libc::uid_t,
     /// exit code of the child
     code: ExitCode,
+    /// `si_code` of the siginfo
+    raw_code: c_int,
 }
 impl ExitInfo {
     /// # Safety
@@ -172,21 +1610,122 @@ impl ExitInfo {
     /// * `siginfo` - Must be retrieved via either `waitid` or `SignalFd` or handler
     ///   registered via `sigaction` or via `sigwaitinfo`/`sigtimedwait`.
     pub unsafe fn new(siginfo: siginfo_t) -> ExitInfo {
-        let status = siginfo.si_status();
+        Self::from_parts(
+            siginfo.si_pid(),
+            siginfo.si_uid(),
+            siginfo.si_code,
+            siginfo.si_status(),
+        )
+    }
+
+    /// Decode a `SIGCHLD` read from `SignalFd` that is sent due to the child
+    /// terminating.
+    ///
+    /// Returns `None` if `siginfo` is not a `SIGCHLD`, or if its `ssi_code` is
+    /// not one of `CLD_EXITED`, `CLD_KILLED` and `CLD_DUMPED`, e.g. it is
+    /// sent due to the child being stopped or continued.
+    ///
+    /// **NOTE that `SIGCHLD` can be coalesced, so there might be fewer of them
+    /// than children terminated, and reading it does not reap the child.**
+    /// Use it for being notified, but rely on `waitid`, e.g. via
+    /// `PidFd::waitpid` or `reap`, for reaping every child.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{fork, raise, _exit, CLD_STOPPED, SIGSTOP};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(Signal::Sigchld).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid == 0 { // child
+    ///         unsafe { _exit(9) };
+    ///     }
+    ///
+    ///     let exit_info = ExitInfo::from_signalfd(&signalfd.read_one().await.unwrap()).unwrap();
+    ///     assert_eq!(exit_info.get_pid(), pid);
+    ///     assert_eq!(exit_info.get_code(), ExitCode::Exited(9));
+    ///
+    ///     assert_eq!(PidFd::open(pid).unwrap().waitpid().await.unwrap(), exit_info);
+    ///
+    ///     // A stopped child is not mistaken for a terminated one.
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid == 0 { // child
+    ///         unsafe {
+    ///             raise(SIGSTOP);
+    ///             _exit(0);
+    ///         }
+    ///     }
+    ///
+    ///     let siginfo = signalfd.read_one().await.unwrap();
+    ///     assert_eq!(siginfo.ssi_code, CLD_STOPPED);
+    ///     assert!(ExitInfo::from_signalfd(&siginfo).is_none());
+    ///
+    ///     let pidfd = PidFd::open(pid).unwrap();
+    ///     pidfd.send_signal(Signal::Sigkill, None).unwrap();
+    ///
+    ///     let exit_info = ExitInfo::from_signalfd(&signalfd.read_one().await.unwrap()).unwrap();
+    ///     assert_eq!(exit_info.get_code(), ExitCode::Killed(Signal::Sigkill));
+    ///     assert_eq!(pidfd.waitpid().await.unwrap(), exit_info);
+    ///
+    ///     // Signals other than `SIGCHLD` are rejected.
+    ///     let mut siginfo = siginfo;
+    ///     siginfo.ssi_signo = i32::from(Signal::Sigusr1) as u32;
+    ///     siginfo.ssi_code = libc::CLD_EXITED;
+    ///     assert!(ExitInfo::from_signalfd(&siginfo).is_none());
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn from_signalfd(siginfo: &libc::signalfd_siginfo) -> Option<ExitInfo> {
+        if siginfo.ssi_signo as c_int != libc::SIGCHLD {
+            return None;
+        }
+
+        match siginfo.ssi_code {
+            libc::CLD_EXITED | libc::CLD_KILLED | libc::CLD_DUMPED => Some(Self::from_parts(
+                siginfo.ssi_pid as pid_t,
+                siginfo.ssi_uid,
+                siginfo.ssi_code,
+                siginfo.ssi_status,
+            )),
+            _ => None,
+        }
+    }
+
+    fn from_parts(pid: pid_t, uid: libc::uid_t, raw_code: c_int, status: c_int) -> ExitInfo {
         let code =
-            if siginfo.si_code == libc::CLD_EXITED {
+            if raw_code == libc::CLD_EXITED {
                 ExitCode::Exited(status)
             } else {
-                ExitCode::Killed(Signal::try_from(status).unwrap())
+                Signal::try_from(status)
+                    .map(ExitCode::Killed)
+                    .unwrap_or(ExitCode::KilledRaw(status))
             }
         ;
 
         ExitInfo {
-            uid: siginfo.si_uid(),
+            pid,
+            uid,
             code,
+            raw_code,
         }
     }
 
+    /// pid of the child
+    pub fn get_pid(&self) -> pid_t {
+        self.pid
+    }
+
     /// uid of the process when it exits
     pub fn get_uid(&self) -> libc::uid_t {
         self.uid
@@ -196,4 +1735,143 @@ impl ExitInfo {
     pub fn get_code(&self) -> ExitCode {
         self.code
     }
+
+    /// Whether the child exits with 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::ptr::null_mut;
+    /// use libc::{fork, kill, pause, syscall, _exit, SIGKILL, SYS_rt_sigaction};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let spawn = |exit_code: Option<i32>| {
+    ///         let pid = unsafe { fork() };
+    ///         assert!(pid >= 0);
+    ///         if pid == 0 { // child
+    ///             match exit_code {
+    ///                 Some(exit_code) => unsafe { _exit(exit_code) },
+    ///                 None => loop { unsafe { pause() }; },
+    ///             }
+    ///         }
+    ///         (pid, PidFd::open(pid).unwrap())
+    ///     };
+    ///
+    ///     let (_, pidfd) = spawn(Some(0));
+    ///     let exitinfo = pidfd.waitpid().await.unwrap();
+    ///     assert_eq!(exitinfo.get_code(), ExitCode::Exited(0));
+    ///     assert!(exitinfo.success());
+    ///
+    ///     let (_, pidfd) = spawn(Some(42));
+    ///     let exitinfo = pidfd.waitpid().await.unwrap();
+    ///     assert_eq!(exitinfo.get_code(), ExitCode::Exited(42));
+    ///     assert!(!exitinfo.success());
+    ///
+    ///     let (pid, pidfd) = spawn(None);
+    ///     assert_eq!(unsafe { kill(pid, SIGKILL) }, 0);
+    ///     let exitinfo = pidfd.waitpid().await.unwrap();
+    ///     assert_eq!(exitinfo.get_code(), ExitCode::Killed(Signal::Sigkill));
+    ///     assert!(!exitinfo.success());
+    ///
+    ///     // Signal 32 is reserved by glibc and has no `Signal` counterpart.
+    ///     //
+    ///     // It might be inherited as ignored and glibc refuses to change its
+    ///     // disposition, so reset it to `SIG_DFL` via the raw syscall.
+    ///     let sigaction = [0_u64; 4];
+    ///     let ret = unsafe {
+    ///         syscall(SYS_rt_sigaction, 32, sigaction.as_ptr(), null_mut::<u64>(), 8)
+    ///     };
+    ///     assert_eq!(ret, 0);
+    ///
+    ///     let (pid, pidfd) = spawn(None);
+    ///     assert_eq!(unsafe { kill(pid, 32) }, 0);
+    ///     let exitinfo = pidfd.waitpid().await.unwrap();
+    ///     assert_eq!(exitinfo.get_code(), ExitCode::KilledRaw(32));
+    ///     assert!(!exitinfo.success());
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn success(&self) -> bool {
+        self.code == ExitCode::Exited(0)
+    }
+
+    /// Whether the child dumped core when killed, i.e. `si_code` is `CLD_DUMPED`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::ffi::CString;
+    /// use std::fs;
+    /// use std::os::unix::ffi::OsStrExt;
+    /// use libc::{abort, chdir, fork, getpid, getrlimit, rlimit, setrlimit};
+    /// use libc::{RLIMIT_CORE, CLD_DUMPED, CLD_KILLED};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     // A core_pattern piping the core into a program ignores both the
+    ///     // rlimit and the cwd, so whether it dumps is up to that program.
+    ///     let core_pattern = fs::read_to_string("/proc/sys/kernel/core_pattern").unwrap();
+    ///     if core_pattern.starts_with('|') {
+    ///         return;
+    ///     }
+    ///
+    ///     let abort_child = |core_dir: Option<CString>| async move {
+    ///         let pid = unsafe { fork() };
+    ///         assert!(pid >= 0);
+    ///         if pid == 0 { // child
+    ///             unsafe {
+    ///                 let mut limit = rlimit { rlim_cur: 0, rlim_max: 0 };
+    ///                 if let Some(dir) = &core_dir {
+    ///                     getrlimit(RLIMIT_CORE, &mut limit);
+    ///                     limit.rlim_cur = limit.rlim_max;
+    ///                     chdir(dir.as_ptr());
+    ///                 }
+    ///                 setrlimit(RLIMIT_CORE, &limit);
+    ///                 abort();
+    ///             }
+    ///         }
+    ///
+    ///         let exitinfo = PidFd::open(pid).unwrap().waitpid().await.unwrap();
+    ///         assert_eq!(exitinfo.get_code(), ExitCode::Killed(Signal::Sigabrt));
+    ///         exitinfo
+    ///     };
+    ///
+    ///     let exitinfo = abort_child(None).await;
+    ///     assert_eq!(exitinfo.raw_code(), CLD_KILLED);
+    ///     assert!(!exitinfo.dumped_core());
+    ///
+    ///     // No core can be dumped if the hard limit is 0.
+    ///     let mut limit = rlimit { rlim_cur: 0, rlim_max: 0 };
+    ///     assert_eq!(unsafe { getrlimit(RLIMIT_CORE, &mut limit) }, 0);
+    ///     if limit.rlim_max == 0 {
+    ///         return;
+    ///     }
+    ///
+    ///     // Dump the core into a temporary directory, in case core_pattern
+    ///     // is relative.
+    ///     let dir = std::env::temp_dir().join(format!("dumped_core-{}", unsafe { getpid() }));
+    ///     fs::create_dir(&dir).unwrap();
+    ///
+    ///     let core_dir = CString::new(dir.as_os_str().as_bytes()).unwrap();
+    ///     let exitinfo = abort_child(Some(core_dir)).await;
+    ///     fs::remove_dir_all(&dir).unwrap();
+    ///
+    ///     assert_eq!(exitinfo.raw_code(), CLD_DUMPED);
+    ///     assert!(exitinfo.dumped_core());
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn dumped_core(&self) -> bool {
+        self.raw_code == libc::CLD_DUMPED
+    }
+
+    /// `si_code` of the child, e.g. `CLD_EXITED`, `CLD_KILLED` or `CLD_DUMPED`.
+    pub fn raw_code(&self) -> c_int {
+        self.raw_code
+    }
 }
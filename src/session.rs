@@ -0,0 +1,118 @@
+use std::fs;
+use std::io::{Result, Error};
+
+use crate::{pid_t, Signal};
+
+/// Returns `(pgid, sid)` parsed from the content of `/proc/<pid>/stat`.
+fn parse_stat(stat: &str) -> Option<(pid_t, pid_t)> {
+    // `comm` is enclosed in parentheses and might contain spaces or parentheses.
+    let mut fields = stat[stat.rfind(')')? + 1..].split_whitespace();
+
+    // Skips `state` and `ppid`.
+    let pgid = fields.nth(2)?.parse().ok()?;
+    let sid = fields.next()?.parse().ok()?;
+
+    Some((pgid, sid))
+}
+
+/// Send `signal` to every process in session `sid`.
+///
+/// There is no syscall for this, so it scans `/proc/*/stat` for process groups
+/// in the session and signals each of them using `killpg`, which costs one read
+/// per process on the system.
+///
+/// Processes that join the session during the scan might be missed.
+///
+/// Returns an error of `ESRCH` if no process is found in the session.
+///
+/// # Example
+///
+/// ```
+/// use std::os::unix::io::RawFd;
+/// use libc::{fork, pause, pipe, read, setsid, write, c_void};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let mut fds: [RawFd; 2] = [-1, -1];
+///     assert_eq!(0, unsafe { pipe(fds.as_mut_ptr()) });
+///
+///     let pid = unsafe { fork() };
+///     assert!(pid >= 0);
+///     if pid == 0 { // child
+///         unsafe {
+///             assert!(setsid() >= 0);
+///
+///             let grandchild = fork();
+///             assert!(grandchild >= 0);
+///             if grandchild != 0 {
+///                 write(fds[1], &grandchild as *const _ as *const c_void, 4);
+///             }
+///
+///             loop {
+///                 pause();
+///             }
+///         }
+///     }
+///
+///     let mut grandchild: pid_t = -1;
+///     let cnt = unsafe { read(fds[0], &mut grandchild as *mut _ as *mut c_void, 4) };
+///     assert_eq!(cnt, 4);
+///
+///     let child_pidfd = PidFd::open(pid).unwrap();
+///     let grandchild_pidfd = PidFd::open(grandchild).unwrap();
+///
+///     send_signal_to_session(pid, Signal::Sigterm).unwrap();
+///
+///     grandchild_pidfd.wait_for_terminate().await.unwrap();
+///     match child_pidfd.waitpid().await.unwrap().get_code() {
+///         ExitCode::Killed(signal) => assert_eq!(signal, Signal::Sigterm),
+///         code => panic!("Unexpected exit code {:#?}", code),
+///     }
+/// }
+///
+/// f();
+/// ```
+pub fn send_signal_to_session(sid: pid_t, signal: Signal) -> Result<()> {
+    let mut pgids = Vec::new();
+
+    for entry in fs::read_dir("/proc")? {
+        let entry = entry?;
+
+        let is_pid = entry.file_name()
+            .to_str()
+            .is_some_and(|name| name.parse::<pid_t>().is_ok());
+        if !is_pid {
+            continue;
+        }
+
+        // The process might have exited since `read_dir`.
+        let stat = match fs::read_to_string(entry.path().join("stat")) {
+            Ok(stat) => stat,
+            Err(_) => continue,
+        };
+
+        if let Some((pgid, process_sid)) = parse_stat(&stat) {
+            if process_sid == sid && !pgids.contains(&pgid) {
+                pgids.push(pgid);
+            }
+        }
+    }
+
+    if pgids.is_empty() {
+        return Err(Error::from_raw_os_error(libc::ESRCH));
+    }
+
+    for pgid in pgids {
+        if unsafe { libc::killpg(pgid, signal.into()) } < 0 {
+            let err = Error::last_os_error();
+
+            // The whole process group might have exited since the scan.
+            if err.raw_os_error() != Some(libc::ESRCH) {
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(())
+}
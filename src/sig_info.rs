@@ -0,0 +1,216 @@
+use std::convert::TryFrom;
+
+use libc::{signalfd_siginfo, uid_t};
+
+use crate::{pid_t, Signal, UnknownSignal};
+
+/// Typed accessors over `signalfd_siginfo`.
+///
+/// Which fields are meaningful depends on `code()`:
+///  - `SI_USER` (sent by `kill`) and `SI_TKILL` (sent by `tgkill`): `sender_pid`
+///    and `sender_uid`;
+///  - `SI_QUEUE` (sent by `sigqueue`): `sender_pid`, `sender_uid`, `value_int`
+///    and `value_ptr`;
+///  - `CLD_*` codes of `SIGCHLD` (sent when a child changes state): `sender_pid`
+///    and `sender_uid` of the child, `child_status`, `child_utime` and
+///    `child_stime`;
+///  - `SI_KERNEL` and other positive codes (generated by the kernel): none of
+///    them, `sender_pid` and `sender_uid` are `0`.
+///
+/// # Example
+///
+/// ```
+/// use libc::{getpid, getuid, sigqueue, sigval, SI_QUEUE};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let signalfd = SignalFd::new({
+///         let mut signal_mask = SignalMask::new();
+///         signal_mask.add(Signal::Sigusr1).unwrap();
+///         signal_mask
+///     }).unwrap();
+///
+///     let value = sigval { sival_ptr: 42 as *mut _ };
+///     assert_eq!(0, unsafe { sigqueue(getpid(), Signal::Sigusr1.into(), value) });
+///
+///     let siginfo = signalfd.read_typed().await.unwrap()[0];
+///     assert_eq!(siginfo.signal(), Ok(Signal::Sigusr1));
+///     assert_eq!(siginfo.code(), SI_QUEUE);
+///     assert_eq!(siginfo.sender_pid(), unsafe { getpid() });
+///     assert_eq!(siginfo.sender_uid(), unsafe { getuid() });
+///     assert_eq!(siginfo.value_ptr(), 42);
+///     # #[cfg(target_endian = "little")]
+///     assert_eq!(siginfo.value_int(), 42);
+/// }
+///
+/// f();
+/// ```
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct SigInfo(signalfd_siginfo);
+impl From<signalfd_siginfo> for SigInfo {
+    fn from(siginfo: signalfd_siginfo) -> Self {
+        Self(siginfo)
+    }
+}
+impl SigInfo {
+    /// Retrieve the underlying `signalfd_siginfo`.
+    pub fn as_raw(&self) -> &signalfd_siginfo {
+        &self.0
+    }
+
+    /// Retrieve the underlying `signalfd_siginfo`.
+    pub fn into_raw(self) -> signalfd_siginfo {
+        self.0
+    }
+
+    /// The signal received.
+    ///
+    /// Fails with `UnknownSignal` if `Signal` cannot represent it, e.g.
+    /// `SIGSTKFLT`, which is included by `SignalMask::new_full` and
+    /// `SignalMask::fill`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{getpid, kill, sigaddset, SIGSTKFLT};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         unsafe { sigaddset(signal_mask.as_sigset_mut(), SIGSTKFLT) };
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     assert_eq!(0, unsafe { kill(getpid(), SIGSTKFLT) });
+    ///
+    ///     let siginfo = signalfd.read_typed().await.unwrap()[0];
+    ///     assert_eq!(siginfo.signal(), Err(UnknownSignal(SIGSTKFLT)));
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn signal(&self) -> Result<Signal, UnknownSignal> {
+        Signal::try_from(self.0.ssi_signo as i32)
+    }
+
+    /// `si_code` of the signal, which tells how it is sent.
+    pub fn code(&self) -> i32 {
+        self.0.ssi_code
+    }
+
+    /// pid of the sender, only meaningful for `SI_USER`, `SI_TKILL` and `SI_QUEUE`.
+    ///
+    /// Signals generated by the kernel report `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{fork, getppid, kill, _exit, SI_USER};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(Signal::Sigusr1).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid == 0 { // child
+    ///         unsafe {
+    ///             kill(getppid(), Signal::Sigusr1.into());
+    ///             _exit(0);
+    ///         }
+    ///     }
+    ///
+    ///     let siginfo = signalfd.read_typed().await.unwrap()[0];
+    ///     assert_eq!(siginfo.code(), SI_USER);
+    ///     assert_eq!(siginfo.sender_pid(), pid);
+    ///
+    ///     PidFd::open(pid).unwrap().waitpid().await.unwrap();
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn sender_pid(&self) -> pid_t {
+        self.0.ssi_pid as pid_t
+    }
+
+    /// Real uid of the sender, only meaningful for `SI_USER`, `SI_TKILL` and
+    /// `SI_QUEUE`.
+    ///
+    /// Signals generated by the kernel report `0`.
+    pub fn sender_uid(&self) -> uid_t {
+        self.0.ssi_uid
+    }
+
+    /// Integer value sent along with the signal by `sigqueue`.
+    pub fn value_int(&self) -> i32 {
+        self.0.ssi_int
+    }
+
+    /// Pointer value sent along with the signal by `sigqueue`.
+    pub fn value_ptr(&self) -> u64 {
+        self.0.ssi_ptr
+    }
+
+    /// Exit code of the child for `CLD_EXITED`, otherwise the signal that
+    /// changed its state, only meaningful for `SIGCHLD`.
+    pub fn child_status(&self) -> i32 {
+        self.0.ssi_status
+    }
+
+    /// User CPU time consumed by the child in clock ticks (see
+    /// `sysconf(_SC_CLK_TCK)`), only meaningful for `SIGCHLD`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use libc::{fork, _exit, CLD_EXITED};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(Signal::Sigchld).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     let pid = unsafe { fork() };
+    ///     assert!(pid >= 0);
+    ///     if pid == 0 { // child
+    ///         let start = Instant::now();
+    ///         while start.elapsed() < Duration::from_millis(200) {}
+    ///         unsafe { _exit(4) };
+    ///     }
+    ///
+    ///     let siginfo = signalfd.read_typed().await.unwrap()[0];
+    ///     assert_eq!(siginfo.signal(), Ok(Signal::Sigchld));
+    ///     assert_eq!(siginfo.code(), CLD_EXITED);
+    ///     assert_eq!(siginfo.sender_pid(), pid);
+    ///     assert_eq!(siginfo.child_status(), 4);
+    ///     assert!(siginfo.child_utime() > 0);
+    ///
+    ///     PidFd::open(pid).unwrap().waitpid().await.unwrap();
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn child_utime(&self) -> u64 {
+        self.0.ssi_utime
+    }
+
+    /// System CPU time consumed by the child in clock ticks (see
+    /// `sysconf(_SC_CLK_TCK)`), only meaningful for `SIGCHLD`.
+    pub fn child_stime(&self) -> u64 {
+        self.0.ssi_stime
+    }
+}
@@ -1,36 +1,264 @@
-use num_enum::{IntoPrimitive, TryFromPrimitive};
-
-// Here it relies on the compiler to check that i32 == c_int
-#[repr(i32)]
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
-pub enum Signal {
-    Sigchld   = libc::SIGCHLD,
-    Sigcont   = libc::SIGCONT,
-    Sigtstp   = libc::SIGTSTP,
-    Sigttin   = libc::SIGTTIN,
-    Sigttou   = libc::SIGTTOU,
-    Sigurg    = libc::SIGURG,
-    Sigwinch  = libc::SIGWINCH,
-    Sigabrt   = libc::SIGABRT,
-    Sigalrm   = libc::SIGALRM,
-    Sigbus    = libc::SIGBUS,
-    Sigfpe    = libc::SIGFPE,
-    Sighup    = libc::SIGHUP,
-    Sigill    = libc::SIGILL,
-    Sigint    = libc::SIGINT,
-    Sigio     = libc::SIGIO,
-    Sigkill   = libc::SIGKILL,
-    Sigpipe   = libc::SIGPIPE,
-    Sigprof   = libc::SIGPROF,
-    Sigpwr    = libc::SIGPWR,
-    Sigquit   = libc::SIGQUIT,
-    Sigsegv   = libc::SIGSEGV,
-    Sigsys    = libc::SIGSYS,
-    Sigterm   = libc::SIGTERM,
-    Sigtrap   = libc::SIGTRAP,
-    Sigusr1   = libc::SIGUSR1,
-    Sigusr2   = libc::SIGUSR2,
-    Sigvtalrm = libc::SIGVTALRM,
-    Sigxcpu   = libc::SIGXCPU,
-    Sigxfsz   = libc::SIGXFSZ,
+use std::convert::TryFrom;
+use std::fmt;
+
+macro_rules! signals {
+    ( $( $variant:ident = $signum:ident, )* ) => {
+        #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+        pub enum Signal {
+            $( $variant, )*
+            /// Real-time signal `SIGRTMIN() + offset`.
+            ///
+            /// Since `SIGRTMIN` is only known at runtime, use `Signal::realtime`
+            /// to create one with a validated offset.
+            Realtime(u8),
+        }
+
+        #[cfg(feature = "serde")]
+        impl Signal {
+            fn name(&self) -> Option<&'static str> {
+                match self {
+                    $( Signal::$variant => Some(stringify!($signum)), )*
+                    Signal::Realtime(_) => None,
+                }
+            }
+
+            fn from_name(name: &str) -> Option<Signal> {
+                match name {
+                    $( stringify!($signum) => Some(Signal::$variant), )*
+                    _ => Signal::realtime(name.strip_prefix("SIGRTMIN+")?.parse().ok()?),
+                }
+            }
+        }
+
+        impl From<Signal> for i32 {
+            fn from(signal: Signal) -> i32 {
+                match signal {
+                    $( Signal::$variant => libc::$signum, )*
+                    Signal::Realtime(offset) => libc::SIGRTMIN() + offset as i32,
+                }
+            }
+        }
+
+        impl TryFrom<i32> for Signal {
+            type Error = UnknownSignal;
+
+            fn try_from(signum: i32) -> Result<Self, Self::Error> {
+                match signum {
+                    $( libc::$signum => Ok(Signal::$variant), )*
+                    _ if (libc::SIGRTMIN()..=libc::SIGRTMAX()).contains(&signum) => {
+                        Ok(Signal::Realtime((signum - libc::SIGRTMIN()) as u8))
+                    },
+                    _ => Err(UnknownSignal(signum)),
+                }
+            }
+        }
+    };
+}
+
+signals! {
+    Sigchld   = SIGCHLD,
+    Sigcont   = SIGCONT,
+    Sigtstp   = SIGTSTP,
+    Sigttin   = SIGTTIN,
+    Sigttou   = SIGTTOU,
+    Sigurg    = SIGURG,
+    Sigwinch  = SIGWINCH,
+    Sigabrt   = SIGABRT,
+    Sigalrm   = SIGALRM,
+    Sigbus    = SIGBUS,
+    Sigfpe    = SIGFPE,
+    Sighup    = SIGHUP,
+    Sigill    = SIGILL,
+    Sigint    = SIGINT,
+    Sigio     = SIGIO,
+    Sigkill   = SIGKILL,
+    Sigstop   = SIGSTOP,
+    Sigpipe   = SIGPIPE,
+    Sigprof   = SIGPROF,
+    Sigpwr    = SIGPWR,
+    Sigquit   = SIGQUIT,
+    Sigsegv   = SIGSEGV,
+    Sigsys    = SIGSYS,
+    Sigterm   = SIGTERM,
+    Sigtrap   = SIGTRAP,
+    Sigusr1   = SIGUSR1,
+    Sigusr2   = SIGUSR2,
+    Sigvtalrm = SIGVTALRM,
+    Sigxcpu   = SIGXCPU,
+    Sigxfsz   = SIGXFSZ,
+}
+
+impl Signal {
+    /// Returns the signal number.
+    pub fn as_raw(&self) -> i32 {
+        (*self).into()
+    }
+
+    /// Returns the `Signal` of `signum`, or `None` if it is not a known signal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let signal = Signal::from_raw(libc::SIGTERM).unwrap();
+    /// assert_eq!(signal, Signal::Sigterm);
+    /// assert_eq!(signal.as_raw(), libc::SIGTERM);
+    /// assert!(!signal.is_realtime());
+    ///
+    /// let signal = Signal::from_raw(libc::SIGRTMIN() + 2).unwrap();
+    /// assert_eq!(signal, Signal::Realtime(2));
+    /// assert!(signal.is_realtime());
+    ///
+    /// assert_eq!(Signal::from_raw(0), None);
+    /// ```
+    pub fn from_raw(signum: i32) -> Option<Signal> {
+        Signal::try_from(signum).ok()
+    }
+
+    /// Whether the signal is in `SIGRTMIN()..=SIGRTMAX()`.
+    pub fn is_realtime(&self) -> bool {
+        (libc::SIGRTMIN()..=libc::SIGRTMAX()).contains(&self.as_raw())
+    }
+
+    /// Returns the real-time signal `SIGRTMIN() + offset`, or `None` if it is
+    /// greater than `SIGRTMAX()`.
+    ///
+    /// Unlike standard signals, real-time signals are queued instead of
+    /// coalesced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{kill, getpid};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signal = Signal::realtime(1).unwrap();
+    ///
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(signal).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     for _ in 0..2 {
+    ///         assert_eq!(0, unsafe { kill(getpid(), signal.into()) });
+    ///     }
+    ///
+    ///     let siginfos = signalfd.read().await.unwrap();
+    ///     assert_eq!(siginfos.len(), 2);
+    ///     for siginfo in siginfos {
+    ///         assert_eq!(siginfo.ssi_signo as i32, signal.into());
+    ///     }
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn realtime(offset: u8) -> Option<Signal> {
+        if libc::SIGRTMIN() + offset as i32 <= libc::SIGRTMAX() {
+            Some(Signal::Realtime(offset))
+        } else {
+            None
+        }
+    }
+}
+
+/// Error of converting a number that is not a known signal into `Signal`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct UnknownSignal(pub i32);
+impl fmt::Display for UnknownSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown signal number {}", self.0)
+    }
+}
+impl std::error::Error for UnknownSignal {}
+
+/// Serialized as its name, e.g. `"SIGKILL"` or `"SIGRTMIN+1"`.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use async_linux_spec_fd::*;
+///
+/// let json = serde_json::to_string(&Signal::Sigkill).unwrap();
+/// assert_eq!(json, r#""SIGKILL""#);
+/// assert_eq!(serde_json::from_str::<Signal>(&json).unwrap(), Signal::Sigkill);
+///
+/// let signal = Signal::realtime(3).unwrap();
+/// let json = serde_json::to_string(&signal).unwrap();
+/// assert_eq!(json, r#""SIGRTMIN+3""#);
+/// assert_eq!(serde_json::from_str::<Signal>(&json).unwrap(), signal);
+///
+/// assert!(serde_json::from_str::<Signal>(r#""SIGFOO""#).is_err());
+/// assert!(serde_json::from_str::<Signal>(r#""SIGRTMIN+200""#).is_err());
+/// assert!(serde_json::from_str::<Signal>(r#""SIGRTMIN+x""#).is_err());
+/// assert!(serde_json::from_str::<Signal>("9").is_err());
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for Signal {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (self.name(), self) {
+            (Some(name), _) => serializer.serialize_str(name),
+            (None, Signal::Realtime(offset)) => {
+                serializer.collect_str(&format_args!("SIGRTMIN+{}", offset))
+            },
+            (None, _) => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Signal {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        Signal::from_name(&name).ok_or_else(|| {
+            serde::de::Error::custom(format_args!("unknown signal {}", name))
+        })
+    }
+}
+
+/// Fails on signals like `SIGSTKFLT` that `Signal` does not have.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "nix")] {
+/// use std::convert::TryFrom;
+/// use async_linux_spec_fd::*;
+///
+/// let signal = Signal::try_from(nix::sys::signal::Signal::SIGTERM).unwrap();
+/// assert_eq!(signal, Signal::Sigterm);
+/// assert_eq!(
+///     nix::sys::signal::Signal::try_from(signal).unwrap(),
+///     nix::sys::signal::Signal::SIGTERM
+/// );
+///
+/// assert!(Signal::try_from(nix::sys::signal::Signal::SIGSTKFLT).is_err());
+/// assert!(nix::sys::signal::Signal::try_from(Signal::realtime(1).unwrap()).is_err());
+/// # }
+/// ```
+#[cfg(feature = "nix")]
+impl TryFrom<nix::sys::signal::Signal> for Signal {
+    type Error = UnknownSignal;
+
+    fn try_from(signal: nix::sys::signal::Signal) -> Result<Self, Self::Error> {
+        Signal::try_from(signal as i32)
+    }
+}
+
+/// Fails on `Signal::Realtime`, which `nix::sys::signal::Signal` does not have.
+#[cfg(feature = "nix")]
+impl TryFrom<Signal> for nix::sys::signal::Signal {
+    type Error = UnknownSignal;
+
+    fn try_from(signal: Signal) -> Result<Self, Self::Error> {
+        let signum: i32 = signal.into();
+
+        nix::sys::signal::Signal::try_from(signum).map_err(|_| UnknownSignal(signum))
+    }
 }
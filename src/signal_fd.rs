@@ -1,17 +1,24 @@
-use std::io::{Result, Error};
-use std::mem::size_of;
+use std::collections::HashMap;
+use std::io::{Result, Error, ErrorKind};
+use std::mem::{size_of, size_of_val};
+use std::os::unix::io::AsRawFd;
+use std::pin::Pin;
+use std::future::poll_fn;
+use std::task::{Context, Poll, ready};
 
 pub use libc::signalfd_siginfo;
 
-use libc::{signalfd, SFD_CLOEXEC, SFD_NONBLOCK};
+use libc::{c_int, signalfd, SFD_CLOEXEC, SFD_NONBLOCK};
 
 use tokio::io::unix::AsyncFd;
-use tokio::io::Interest;
+use tokio::io::{AsyncRead, Interest, ReadBuf};
 
 pub use arrayvec::ArrayVec;
 
-use crate::fd::Fd;
-use crate::SignalMask;
+use futures_core::Stream;
+
+use crate::fd::{Fd, poll_read_buf};
+use crate::{SigInfo, Signal, SignalMask};
 
 /// `SignalFd` for async way of accepting signals.
 ///
@@ -63,6 +70,10 @@ use crate::SignalMask;
 /// ```
 pub struct SignalFd {
     inner: AsyncFd<Fd>,
+    /// Unblocks the signals blocked by `SignalFd::new_scoped` on drop.
+    ///
+    /// Declared after `inner` so that the fd is closed before they are unblocked.
+    _guard: Option<UnblockOnDrop>,
 }
 impl SignalFd {
     /// Returns a `SignalFd` that is close-on-exec.
@@ -83,9 +94,97 @@ impl SignalFd {
     ///     - `SIGFPE`;
     ///     - `SIGILL`;
     ///     - `SIGSEGV`
-    pub fn new(sigmask: SignalMask) -> Result<Self> {
+    ///
+    ///    otherwise `Error::ForbiddenSignal` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut signal_mask = SignalMask::new();
+    /// signal_mask.add(Signal::Sigkill).unwrap();
+    ///
+    /// match SignalFd::new(signal_mask) {
+    ///     Err(Error::ForbiddenSignal(signal)) => assert_eq!(signal, Signal::Sigkill),
+    ///     Err(err) => panic!("Unexpected error {}", err),
+    ///     Ok(_) => panic!("SIGKILL is accepted"),
+    /// }
+    /// ```
+    pub fn new(sigmask: SignalMask) -> std::result::Result<Self, crate::Error> {
+        check_forbidden(&sigmask)?;
+
         sigmask.block()?;
 
+        Ok(Self::from_sigmask(&sigmask, None)?)
+    }
+
+    /// Same as `SignalFd::new`, but signals in `sigmask` that are not blocked
+    /// before the call are unblocked again when the returned `SignalFd` is
+    /// dropped.
+    ///
+    /// Signals already blocked are left alone, so scoped `SignalFd`s can be
+    /// dropped in any order. However, a signal newly blocked by this call is
+    /// unblocked even if another `SignalFd` created later also watches it.
+    ///
+    /// Since the signal mask is per-thread, it must be dropped on the thread
+    /// that created it, otherwise the signals are unblocked in the dropping
+    /// thread instead.
+    ///
+    /// Signals that are still pending once unblocked are delivered according to
+    /// their disposition, which is to terminate the process for most of them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signalfd = SignalFd::new_scoped({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(Signal::Sigusr2).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///     assert!(SignalMask::current().unwrap().is_member(Signal::Sigusr2).unwrap());
+    ///
+    ///     drop(signalfd);
+    ///     assert!(!SignalMask::current().unwrap().is_member(Signal::Sigusr2).unwrap());
+    ///
+    ///     // Signals blocked before the call stay blocked.
+    ///     let mut signal_mask = SignalMask::new();
+    ///     signal_mask.add(Signal::Sigusr1).unwrap();
+    ///     signal_mask.block().unwrap();
+    ///
+    ///     signal_mask.add(Signal::Sigusr2).unwrap();
+    ///     drop(SignalFd::new_scoped(signal_mask).unwrap());
+    ///
+    ///     let current = SignalMask::current().unwrap();
+    ///     assert!(current.is_member(Signal::Sigusr1).unwrap());
+    ///     assert!(!current.is_member(Signal::Sigusr2).unwrap());
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn new_scoped(sigmask: SignalMask) -> std::result::Result<Self, crate::Error> {
+        check_forbidden(&sigmask)?;
+
+        let old_mask = sigmask.block()?;
+
+        let mut newly_blocked = SignalMask::new();
+        for signal in sigmask.iter() {
+            if !old_mask.is_member(signal)? {
+                newly_blocked.add(signal)?;
+            }
+        }
+
+        // Created before the fd, so that they are unblocked on failure too.
+        let guard = UnblockOnDrop(newly_blocked);
+
+        Ok(Self::from_sigmask(&sigmask, Some(guard))?)
+    }
+
+    fn from_sigmask(sigmask: &SignalMask, guard: Option<UnblockOnDrop>) -> Result<Self> {
         let fd = unsafe {
             signalfd(-1, sigmask.as_sigset(), SFD_NONBLOCK | SFD_CLOEXEC)
         };
@@ -97,43 +196,732 @@ impl SignalFd {
 
         Ok(Self {
             inner: AsyncFd::with_interest(fd, Interest::READABLE)?,
+            _guard: guard,
         })
     }
 
-    async fn read_bytes(&self, out: &mut [u8]) -> Result<usize> {
-        loop {
-            let mut guard = self.inner.readable().await?;
+    /// Replace the set of signals watched by this `SignalFd` in place.
+    ///
+    /// Signals in `sigmask` will be masked, just like `SignalFd::new`.
+    ///
+    /// Signals that are only in the old mask stay blocked, use
+    /// `SignalMask::block_with` with `How::Unblock` if you want them
+    /// to be handled by their disposition again.
+    ///
+    ///  * `sigmask` - has the same restrictions as in `SignalFd::new`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{kill, getpid};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(Signal::Sigusr1).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     signalfd.set_mask({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(Signal::Sigusr2).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     let pid = unsafe { getpid() };
+    ///     assert_eq!(0, unsafe { kill(pid, Signal::Sigusr1.into()) });
+    ///     assert_eq!(0, unsafe { kill(pid, Signal::Sigusr2.into()) });
+    ///
+    ///     let siginfos = signalfd.read().await.unwrap();
+    ///     assert_eq!(siginfos.len(), 1);
+    ///     assert_eq!(siginfos[0].ssi_signo as i32, Signal::Sigusr2.into());
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn set_mask(&self, sigmask: SignalMask) -> std::result::Result<(), crate::Error> {
+        check_forbidden(&sigmask)?;
 
-            match guard.try_io(|inner| -> Result<usize> {
-                let fd = inner.get_ref();
+        sigmask.block()?;
 
-                fd.read(out)
-            }) {
-                Ok(result) => break result,
-                Err(_would_block) => continue,
-            }
+        let fd = unsafe {
+            signalfd(self.inner.as_raw_fd(), sigmask.as_sigset(), SFD_NONBLOCK)
+        };
+        if fd < 0 {
+            Err(Error::last_os_error().into())
+        } else {
+            Ok(())
         }
     }
 
-    /// **NOTE that signals can be coalesced together unless the sender employs
-    /// `sigqueue` to send the signals.**
-    pub async fn read(&self) -> Result<ArrayVec<signalfd_siginfo, 100>> {
+    /// Same as `SignalFd::new`, but verifies that signals are actually delivered
+    /// to the `SignalFd` before returning it.
+    ///
+    /// It sends a probe signal to the calling thread via `tgkill` and consumes
+    /// it from the `SignalFd`, failing if nothing can be read, which catches
+    /// environments where `signalfd` succeeds but delivery is broken.
+    ///
+    /// The probe is the lowest-numbered real-time signal in `sigmask`, or the
+    /// lowest-numbered signal if there is none. Since the calling thread has
+    /// it blocked, it is always queued instead of being handled, and signals
+    /// pending for the whole process are left alone. Prefer including a
+    /// real-time signal, as a standard one already pending for the calling
+    /// thread would be coalesced with the probe.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{getpid, kill};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let probe = Signal::realtime(6).unwrap();
+    ///
+    ///     let mut signal_mask = SignalMask::new();
+    ///     signal_mask.add(Signal::Sigusr1).unwrap();
+    ///     signal_mask.add(probe).unwrap();
+    ///
+    ///     // A signal pending before the probe is not consumed by it.
+    ///     signal_mask.block().unwrap();
+    ///     assert_eq!(0, unsafe { kill(getpid(), Signal::Sigusr1.into()) });
+    ///
+    ///     let signalfd = SignalFd::new_verified(signal_mask).unwrap();
+    ///
+    ///     let siginfos = signalfd.read().await.unwrap();
+    ///     assert_eq!(siginfos.len(), 1);
+    ///     assert_eq!(siginfos[0].ssi_signo as i32, Signal::Sigusr1.into());
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn new_verified(sigmask: SignalMask) -> std::result::Result<Self, crate::Error> {
+        let probe = sigmask.iter()
+            .find(Signal::is_realtime)
+            .or_else(|| sigmask.iter().next())
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "sigmask is empty"))?;
+
+        let signal_fd = Self::new(sigmask)?;
+
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_tgkill,
+                libc::getpid(),
+                libc::syscall(libc::SYS_gettid),
+                c_int::from(probe)
+            )
+        };
+        if ret < 0 {
+            return Err(Error::last_os_error().into());
+        }
+
+        let mut buffer = [0_u8; size_of::<signalfd_siginfo>()];
+        match signal_fd.inner.get_ref().read(&mut buffer) {
+            Ok(_) => Ok(signal_fd),
+            Err(err) if err.kind() == ErrorKind::WouldBlock => Err(Error::other(
+                "signalfd does not receive signals sent to this thread"
+            ).into()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Signals accepted by this `SignalFd`, as the bitmask shown in fdinfo
+    /// where bit `n - 1` stands for signal `n`.
+    pub(crate) fn raw_mask(&self) -> Result<u64> {
+        let path = format!("/proc/self/fdinfo/{}", self.inner.get_ref().as_raw_fd());
+        let fdinfo = std::fs::read_to_string(path)?;
+
+        let mask = fdinfo
+            .lines()
+            .find_map(|line| line.strip_prefix("sigmask:"))
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "no sigmask field in fdinfo"))?
+            .trim();
+
+        u64::from_str_radix(mask, 16).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+
+    fn poll_read_bytes(&self, cx: &mut Context<'_>, out: &mut [u8]) -> Poll<Result<usize>> {
+        let mut buf = ReadBuf::new(out);
+
+        ready!(poll_read_buf(&self.inner, cx, &mut buf))?;
+
+        Poll::Ready(Ok(buf.filled().len()))
+    }
+
+    fn poll_read_n<const N: usize>(&self, cx: &mut Context<'_>)
+        -> Poll<Result<ArrayVec<signalfd_siginfo, N>>>
+    {
+        self.poll_read_bounded(cx, N)
+    }
+
+    /// Read at most `min(max, N)` siginfos.
+    fn poll_read_bounded<const N: usize>(&self, cx: &mut Context<'_>, max: usize)
+        -> Poll<Result<ArrayVec<signalfd_siginfo, N>>>
+    {
         let mut siginfos = ArrayVec::new_const();
 
         let bytes = unsafe {
             core::slice::from_raw_parts_mut(
                 siginfos.as_mut_ptr() as *mut u8,
-                siginfos.capacity() * size_of::<signalfd_siginfo>()
+                max.min(N) * size_of::<signalfd_siginfo>()
             )
         };
 
-        let cnt = self.read_bytes(bytes).await?;
+        let cnt = ready!(self.poll_read_bytes(cx, bytes))?;
         assert_eq!(cnt % size_of::<signalfd_siginfo>(), 0);
 
         let items = cnt / size_of::<signalfd_siginfo>();
 
         unsafe { siginfos.set_len(items) };
 
-        Ok(siginfos)
+        Poll::Ready(Ok(siginfos))
+    }
+
+    /// **NOTE that signals can be coalesced together unless the sender employs
+    /// `sigqueue` to send the signals.**
+    pub async fn read(&self) -> Result<ArrayVec<signalfd_siginfo, 100>> {
+        self.read_n().await
+    }
+
+    /// Same as `SignalFd::read`, but reads at most `N` siginfos, leaving the
+    /// rest for the next read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{kill, getpid};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signal = Signal::realtime(1).unwrap();
+    ///
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(signal).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     for _ in 0..10 {
+    ///         assert_eq!(0, unsafe { kill(getpid(), signal.into()) });
+    ///     }
+    ///
+    ///     assert_eq!(signalfd.read_n::<4>().await.unwrap().len(), 4);
+    ///     assert_eq!(signalfd.read().await.unwrap().len(), 6);
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn read_n<const N: usize>(&self) -> Result<ArrayVec<signalfd_siginfo, N>> {
+        poll_fn(|cx| self.poll_read_n(cx)).await
+    }
+
+    /// Same as `SignalFd::read`, but also returns whether there are still
+    /// siginfos left after the read, i.e. whether they did not all fit.
+    ///
+    /// Being `true` persistently means signals are arriving faster than
+    /// they are read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{getpid, sigqueue, sigval};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signal = Signal::realtime(3).unwrap();
+    ///
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(signal).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     let value = sigval { sival_ptr: std::ptr::null_mut() };
+    ///     for _ in 0..150 {
+    ///         assert_eq!(0, unsafe { sigqueue(getpid(), signal.into(), value) });
+    ///     }
+    ///
+    ///     let (siginfos, more) = signalfd.read_with_stats().await.unwrap();
+    ///     assert_eq!(siginfos.len(), 100);
+    ///     assert!(more);
+    ///
+    ///     let (siginfos, more) = signalfd.read_with_stats().await.unwrap();
+    ///     assert_eq!(siginfos.len(), 50);
+    ///     assert!(!more);
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn read_with_stats(&self) -> Result<(ArrayVec<signalfd_siginfo, 100>, bool)> {
+        let siginfos = self.read().await?;
+
+        let more = self.inner.get_ref().is_readable()?;
+
+        Ok((siginfos, more))
+    }
+
+    /// Same as `SignalFd::read`, but reads at most `max` siginfos, leaving
+    /// the rest for the next read.
+    ///
+    /// Useful for not starving other branches of `tokio::select!` on a flood
+    /// of signals.
+    ///
+    /// Fails with `EINVAL` immediately if `max` is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{kill, getpid};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signal = Signal::realtime(2).unwrap();
+    ///
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(signal).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     for _ in 0..50 {
+    ///         assert_eq!(0, unsafe { kill(getpid(), signal.into()) });
+    ///     }
+    ///
+    ///     for _ in 0..5 {
+    ///         assert_eq!(signalfd.read_bounded(10).await.unwrap().len(), 10);
+    ///     }
+    ///
+    ///     // Fails without waiting for any signal.
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(Signal::realtime(7).unwrap()).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     let err = signalfd.read_bounded(0).await.unwrap_err();
+    ///     assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn read_bounded(&self, max: usize) -> Result<ArrayVec<signalfd_siginfo, 100>> {
+        if max == 0 {
+            return Err(Error::from_raw_os_error(libc::EINVAL));
+        }
+
+        poll_fn(|cx| self.poll_read_bounded(cx, max)).await
+    }
+
+    /// Same as `SignalFd::read`, but reads exactly one siginfo.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{kill, getpid};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(Signal::Sigusr1).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     assert_eq!(0, unsafe { kill(getpid(), Signal::Sigusr1.into()) });
+    ///
+    ///     let siginfo = signalfd.read_one().await.unwrap();
+    ///     assert_eq!(siginfo.ssi_signo as i32, Signal::Sigusr1.into());
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn read_one(&self) -> Result<signalfd_siginfo> {
+        let mut siginfos = self.read_n::<1>().await?;
+
+        Ok(siginfos.pop().unwrap())
+    }
+
+    /// Wait for at least one siginfo, then read and discard all pending
+    /// siginfos through a small stack buffer.
+    ///
+    /// Cheaper than `SignalFd::read` when the siginfos themselves are not
+    /// needed, e.g. when `SIGCHLD` is only used as a cue to call `waitid`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use libc::{kill, getpid};
+    /// use tokio::time::timeout;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signal = Signal::realtime(4).unwrap();
+    ///
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(signal).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     for _ in 0..20 {
+    ///         assert_eq!(0, unsafe { kill(getpid(), signal.into()) });
+    ///     }
+    ///
+    ///     signalfd.drain().await.unwrap();
+    ///
+    ///     let read = timeout(Duration::from_millis(50), signalfd.read()).await;
+    ///     assert!(read.is_err());
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn drain(&self) -> Result<()> {
+        let mut buffer = [0_u8; 4 * size_of::<signalfd_siginfo>()];
+        let mut drained = false;
+
+        poll_fn(|cx| loop {
+            let mut guard = ready!(self.inner.poll_read_ready(cx))?;
+
+            match guard.try_io(|inner| inner.get_ref().read(&mut buffer)) {
+                Ok(result) => drained = result.map(|_| true)?,
+                Err(_would_block) if drained => break Poll::Ready(Ok(())),
+                Err(_would_block) => continue,
+            }
+        }).await
+    }
+
+    /// Same as `SignalFd::read`, but returns `SigInfo`s.
+    pub async fn read_typed(&self) -> Result<ArrayVec<SigInfo, 100>> {
+        Ok(self.read().await?.into_iter().map(SigInfo::from).collect())
     }
+
+    /// Same as `SignalFd::read`, but reads into `out` and returns the number
+    /// of siginfos read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libc::{kill, getpid};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let signal = Signal::realtime(1).unwrap();
+    ///
+    ///     let signalfd = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(signal).unwrap();
+    ///         signal_mask
+    ///     }).unwrap();
+    ///
+    ///     for _ in 0..3 {
+    ///         assert_eq!(0, unsafe { kill(getpid(), signal.into()) });
+    ///     }
+    ///
+    ///     let mut siginfos: [signalfd_siginfo; 8] = unsafe { std::mem::zeroed() };
+    ///
+    ///     let cnt = signalfd.read_into(&mut siginfos).await.unwrap();
+    ///     assert_eq!(cnt, 3);
+    ///     for siginfo in &siginfos[..cnt] {
+    ///         assert_eq!(siginfo.ssi_signo as i32, signal.into());
+    ///     }
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub async fn read_into(&self, out: &mut [signalfd_siginfo]) -> Result<usize> {
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(
+                out.as_mut_ptr() as *mut u8,
+                size_of_val(out)
+            )
+        };
+
+        let cnt = poll_fn(|cx| self.poll_read_bytes(cx, bytes)).await?;
+        assert_eq!(cnt % size_of::<signalfd_siginfo>(), 0);
+
+        Ok(cnt / size_of::<signalfd_siginfo>())
+    }
+
+    /// Convert into a `Stream` yielding siginfos one by one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use libc::{kill, getpid};
+    /// use tokio_stream::StreamExt;
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let mut stream = SignalFd::new({
+    ///         let mut signal_mask = SignalMask::new();
+    ///         signal_mask.add(Signal::Sigusr1).unwrap();
+    ///         signal_mask
+    ///     }).unwrap().into_stream();
+    ///
+    ///     let need_to_stop = Arc::new(AtomicBool::new(false));
+    ///
+    ///     let need_to_stop_cloned = need_to_stop.clone();
+    ///     std::thread::spawn(move || {
+    ///         let pid = unsafe { getpid() };
+    ///
+    ///         let need_to_stop = need_to_stop_cloned;
+    ///
+    ///         while !need_to_stop.load(Ordering::Relaxed) {
+    ///             assert_eq!(0, unsafe { kill(pid, Signal::Sigusr1.into()) });
+    ///         }
+    ///     });
+    ///
+    ///     for _ in 0..1000 {
+    ///         let siginfo = stream.next().await.unwrap().unwrap();
+    ///         assert_eq!(siginfo.ssi_signo as i32, Signal::Sigusr1.into());
+    ///     }
+    ///
+    ///     need_to_stop.store(true, Ordering::Relaxed);
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn into_stream(self) -> SignalStream {
+        SignalStream {
+            inner: self,
+            batch: ArrayVec::new_const().into_iter(),
+        }
+    }
+
+    /// Same as `Fd::close`.
+    ///
+    /// For `SignalFd` created via `SignalFd::new_scoped`, the signals are
+    /// unblocked after the fd is closed.
+    pub fn close(self) -> Result<()> {
+        self.inner.into_inner().close()
+    }
+}
+
+/// Unblocks the signals in the mask on drop.
+struct UnblockOnDrop(SignalMask);
+impl Drop for UnblockOnDrop {
+    fn drop(&mut self) {
+        let ret = unsafe {
+            libc::sigprocmask(libc::SIG_UNBLOCK, self.0.as_sigset(), std::ptr::null_mut())
+        };
+
+        debug_assert!(ret >= 0, "{}", Error::last_os_error());
+    }
+}
+
+/// Read raw `signalfd_siginfo`s as bytes.
+///
+/// The buffer must have room for at least one `signalfd_siginfo`,
+/// otherwise the read fails with `EINVAL`.
+///
+/// # Example
+///
+/// ```
+/// use std::mem::size_of;
+/// use libc::{kill, getpid};
+/// use tokio::io::AsyncReadExt;
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let mut signalfd = SignalFd::new({
+///         let mut signal_mask = SignalMask::new();
+///         signal_mask.add(Signal::Sigusr1).unwrap();
+///         signal_mask
+///     }).unwrap();
+///
+///     assert_eq!(0, unsafe { kill(getpid(), Signal::Sigusr1.into()) });
+///
+///     let mut buf = [0_u8; size_of::<signalfd_siginfo>()];
+///     signalfd.read_exact(&mut buf).await.unwrap();
+///
+///     let siginfo: signalfd_siginfo = unsafe {
+///         std::ptr::read_unaligned(buf.as_ptr() as *const _)
+///     };
+///     assert_eq!(siginfo.ssi_signo as i32, Signal::Sigusr1.into());
+/// }
+///
+/// f();
+/// ```
+impl AsyncRead for SignalFd {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>)
+        -> Poll<Result<()>>
+    {
+        poll_read_buf(&self.inner, cx, buf)
+    }
+}
+
+/// `Stream` of siginfos read from a `SignalFd`, created by
+/// `SignalFd::into_stream`.
+///
+/// Siginfos are read from the kernel in batches, so dropping the stream
+/// discards the rest of the current batch.
+pub struct SignalStream {
+    inner: SignalFd,
+    batch: arrayvec::IntoIter<signalfd_siginfo, 100>,
+}
+impl Stream for SignalStream {
+    type Item = Result<signalfd_siginfo>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if let Some(siginfo) = this.batch.next() {
+                break Poll::Ready(Some(Ok(siginfo)));
+            }
+
+            match ready!(this.inner.poll_read_n(cx)) {
+                Ok(siginfos) => this.batch = siginfos.into_iter(),
+                Err(err) => break Poll::Ready(Some(Err(err))),
+            }
+        }
+    }
+}
+
+/// Signals that cannot be accepted via `SignalFd`.
+const FORBIDDEN_SIGNALS: [Signal; 6] = [
+    Signal::Sigkill,
+    Signal::Sigstop,
+    Signal::Sigbus,
+    Signal::Sigfpe,
+    Signal::Sigill,
+    Signal::Sigsegv,
+];
+
+fn check_forbidden(sigmask: &SignalMask) -> std::result::Result<(), crate::Error> {
+    for signal in FORBIDDEN_SIGNALS {
+        if sigmask.is_member(signal)? {
+            return Err(crate::Error::ForbiddenSignal(signal));
+        }
+    }
+
+    Ok(())
+}
+
+/// Builder of `SignalFd`.
+///
+/// # Example
+///
+/// ```
+/// use libc::{kill, getpid};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let signalfd = Signals::new()
+///         .add(Signal::Sigusr1)
+///         .add(Signal::Sigusr2)
+///         .add(Signal::Sigusr1)
+///         .into_signalfd()
+///         .unwrap();
+///
+///     let pid = unsafe { getpid() };
+///     assert_eq!(0, unsafe { kill(pid, Signal::Sigusr1.into()) });
+///     assert_eq!(0, unsafe { kill(pid, Signal::Sigusr2.into()) });
+///
+///     let mut signos: Vec<i32> = Vec::new();
+///     while signos.len() < 2 {
+///         for siginfo in signalfd.read().await.unwrap() {
+///             signos.push(siginfo.ssi_signo as i32);
+///         }
+///     }
+///     signos.sort_unstable();
+///     assert_eq!(signos, [Signal::Sigusr1.into(), Signal::Sigusr2.into()]);
+///
+///     match Signals::new().add(Signal::Sigkill).into_signalfd() {
+///         Err(Error::ForbiddenSignal(signal)) => assert_eq!(signal, Signal::Sigkill),
+///         Err(err) => panic!("Unexpected error {}", err),
+///         Ok(_) => panic!("SIGKILL is accepted"),
+///     }
+/// }
+///
+/// f();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Signals {
+    signals: Vec<Signal>,
+}
+impl Signals {
+    /// Returns a builder without any signals.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `signal` to be accepted, ignoring duplicates.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, signal: Signal) -> Self {
+        if !self.signals.contains(&signal) {
+            self.signals.push(signal);
+        }
+        self
+    }
+
+    /// Create a `SignalFd` via `SignalFd::new` accepting the added signals.
+    ///
+    /// Returns `Error::ForbiddenSignal` if any of them is forbidden
+    /// in `SignalFd::new`.
+    pub fn into_signalfd(self) -> std::result::Result<SignalFd, crate::Error> {
+        let mut sigmask = SignalMask::new();
+
+        for signal in self.signals {
+            sigmask.add(signal)?;
+        }
+
+        SignalFd::new(sigmask)
+    }
+}
+
+/// Count the siginfos in `siginfos` per `ssi_signo`, e.g. for metrics over
+/// a batch returned by `SignalFd::read`.
+///
+/// **NOTE that unless the sender employs `sigqueue`, signals can be coalesced
+/// together before being read, so the counts can be lower than the number of
+/// times the signals were sent.**
+///
+/// # Example
+///
+/// ```
+/// use async_linux_spec_fd::*;
+///
+/// let siginfo = |signal: Signal| {
+///     let mut siginfo: signalfd_siginfo = unsafe { std::mem::zeroed() };
+///     siginfo.ssi_signo = i32::from(signal) as u32;
+///     siginfo
+/// };
+///
+/// let siginfos = [
+///     siginfo(Signal::Sigusr1),
+///     siginfo(Signal::Sigchld),
+///     siginfo(Signal::Sigusr1),
+///     siginfo(Signal::Sigusr1),
+/// ];
+///
+/// let counts = tally(&siginfos);
+/// assert_eq!(counts.len(), 2);
+/// assert_eq!(counts[&Signal::Sigusr1.into()], 3);
+/// assert_eq!(counts[&Signal::Sigchld.into()], 1);
+///
+/// assert!(tally(&[]).is_empty());
+/// ```
+pub fn tally(siginfos: &[signalfd_siginfo]) -> HashMap<i32, usize> {
+    let mut counts = HashMap::new();
+
+    for siginfo in siginfos {
+        *counts.entry(siginfo.ssi_signo as i32).or_insert(0) += 1;
+    }
+
+    counts
 }
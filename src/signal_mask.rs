@@ -1,11 +1,35 @@
+use std::convert::TryFrom;
 use std::io::{Result, Error};
+use std::marker::PhantomData;
+use std::ptr::{null, null_mut};
 use libc::{
-    sigset_t, SIG_BLOCK,
-    sigemptyset, sigfillset, sigaddset, sigdelset, sigismember, sigprocmask
+    c_int, sigset_t, SIG_BLOCK, SIG_UNBLOCK, SIG_SETMASK,
+    sigemptyset, sigfillset, sigaddset, sigdelset, sigismember, sigprocmask,
+    pthread_sigmask
 };
 
 use crate::Signal;
 
+/// How the signal mask is changed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum How {
+    /// Add the signals to the set of blocked signals.
+    Block,
+    /// Remove the signals from the set of blocked signals.
+    Unblock,
+    /// Set the set of blocked signals to the signals.
+    SetMask,
+}
+impl From<How> for c_int {
+    fn from(how: How) -> c_int {
+        match how {
+            How::Block => SIG_BLOCK,
+            How::Unblock => SIG_UNBLOCK,
+            How::SetMask => SIG_SETMASK,
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct SignalMask {
     mask: sigset_t
@@ -23,10 +47,7 @@ impl SignalMask {
         let mut mask = std::mem::MaybeUninit::<sigset_t>::uninit();
 
         let ret = unsafe { sigemptyset(mask.as_mut_ptr()) };
-        if cfg!(debug_assertions) && ret < 0 {
-            let result: Result<()> = Err(Error::last_os_error());
-            result.unwrap();
-        }
+        debug_assert!(ret >= 0, "{}", Error::last_os_error());
 
         Self { mask: unsafe { mask.assume_init() } }
     }
@@ -36,14 +57,61 @@ impl SignalMask {
         let mut mask = std::mem::MaybeUninit::<sigset_t>::uninit();
 
         let ret = unsafe { sigfillset(mask.as_mut_ptr()) };
-        if cfg!(debug_assertions) && ret < 0 {
-            let result: Result<()> = Err(Error::last_os_error());
-            result.unwrap();
-        }
+        debug_assert!(ret >= 0, "{}", Error::last_os_error());
 
         Self { mask: unsafe { mask.assume_init() } }
     }
 
+    /// Retrieve the signal mask currently blocked, without modifying it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut signal_mask = SignalMask::new();
+    /// signal_mask.add(Signal::Sigusr1).unwrap();
+    /// signal_mask.block().unwrap();
+    ///
+    /// assert!(SignalMask::current().unwrap().is_member(Signal::Sigusr1).unwrap());
+    /// ```
+    pub fn current() -> Result<Self> {
+        let mut mask = std::mem::MaybeUninit::<sigset_t>::uninit();
+
+        // `how` is ignored since `set` is null.
+        if unsafe { sigprocmask(SIG_BLOCK, null(), mask.as_mut_ptr()) } < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(Self { mask: unsafe { mask.assume_init() } })
+        }
+    }
+
+    /// Retrieve the signal mask currently blocked on the calling thread,
+    /// without modifying it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut signal_mask = SignalMask::new();
+    /// signal_mask.add(Signal::Sigusr1).unwrap();
+    /// signal_mask.block_thread().unwrap();
+    ///
+    /// assert!(SignalMask::thread_current().unwrap().is_member(Signal::Sigusr1).unwrap());
+    /// ```
+    pub fn thread_current() -> Result<Self> {
+        let mut mask = std::mem::MaybeUninit::<sigset_t>::uninit();
+
+        // `how` is ignored since `set` is null.
+        let ret = unsafe { pthread_sigmask(SIG_BLOCK, null(), mask.as_mut_ptr()) };
+        if ret != 0 {
+            Err(Error::from_raw_os_error(ret))
+        } else {
+            Ok(Self { mask: unsafe { mask.assume_init() } })
+        }
+    }
+
     /// Add `signal` to the mask.
     pub fn add(&mut self, signal: Signal) -> Result<()> {
         if unsafe { sigaddset(&mut self.mask, signal.into()) } < 0 {
@@ -72,17 +140,205 @@ impl SignalMask {
         }
     }
 
+    /// Iterate over the signals in the mask, in ascending order of their number.
+    ///
+    /// Only numbers representable as `Signal` are yielded: the standard signals
+    /// and real-time signals from `SIGRTMIN()` to `SIGRTMAX()`; numbers reserved
+    /// by libc for internal use are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut signal_mask = SignalMask::new();
+    /// signal_mask.add(Signal::Sigterm).unwrap();
+    /// signal_mask.add(Signal::Sigint).unwrap();
+    /// signal_mask.add(Signal::Sigusr1).unwrap();
+    ///
+    /// let signals: Vec<Signal> = signal_mask.iter().collect();
+    /// assert_eq!(signals, [Signal::Sigint, Signal::Sigusr1, Signal::Sigterm]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = Signal> + '_ {
+        (1..=libc::SIGRTMAX())
+            .filter(move |signum| unsafe { sigismember(&self.mask, *signum) } > 0)
+            .filter_map(|signum| Signal::try_from(signum).ok())
+    }
+
+    /// Remove every signal from the mask.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut signal_mask = SignalMask::new();
+    /// assert!(signal_mask.is_empty());
+    ///
+    /// signal_mask.fill();
+    /// assert!(signal_mask.is_member(Signal::Sigterm).unwrap());
+    /// assert!(signal_mask.len() >= 31);
+    ///
+    /// signal_mask.clear();
+    /// assert!(signal_mask.is_empty());
+    /// assert_eq!(signal_mask.len(), 0);
+    /// ```
+    pub fn clear(&mut self) {
+        let ret = unsafe { sigemptyset(&mut self.mask) };
+        debug_assert!(ret >= 0, "{}", Error::last_os_error());
+    }
+
+    /// Add every signal to the mask.
+    pub fn fill(&mut self) {
+        let ret = unsafe { sigfillset(&mut self.mask) };
+        debug_assert!(ret >= 0, "{}", Error::last_os_error());
+    }
+
+    /// Number of signals in the mask, counted the same way as `SignalMask::iter`.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Test if there is no signal in the mask, counted the same way as
+    /// `SignalMask::iter`.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
     /// Block the signal specified in mask and return the old signal mask.
     pub fn block(&self) -> Result<SignalMask> {
+        self.block_with(How::Block)
+    }
+
+    /// Change the signal mask according to `how` and return the old signal mask.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut sigusr1 = SignalMask::new();
+    /// sigusr1.add(Signal::Sigusr1).unwrap();
+    ///
+    /// let mut sigusr2 = SignalMask::new();
+    /// sigusr2.add(Signal::Sigusr2).unwrap();
+    ///
+    /// let old_mask = sigusr1.block_with(How::Block).unwrap();
+    /// assert!(!old_mask.is_member(Signal::Sigusr1).unwrap());
+    /// assert!(SignalMask::current().unwrap().is_member(Signal::Sigusr1).unwrap());
+    ///
+    /// sigusr2.block_with(How::SetMask).unwrap();
+    /// let current = SignalMask::current().unwrap();
+    /// assert!(!current.is_member(Signal::Sigusr1).unwrap());
+    /// assert!(current.is_member(Signal::Sigusr2).unwrap());
+    ///
+    /// sigusr2.block_with(How::Unblock).unwrap();
+    /// assert!(!SignalMask::current().unwrap().is_member(Signal::Sigusr2).unwrap());
+    ///
+    /// // Restore the mask precisely.
+    /// old_mask.block_with(How::SetMask).unwrap();
+    /// assert!(SignalMask::current().unwrap().iter().eq(old_mask.iter()));
+    /// ```
+    pub fn block_with(&self, how: How) -> Result<SignalMask> {
         let mut old_mask = std::mem::MaybeUninit::<sigset_t>::uninit();
 
-        if unsafe { sigprocmask(SIG_BLOCK, &self.mask, old_mask.as_mut_ptr()) } < 0 {
+        if unsafe { sigprocmask(how.into(), &self.mask, old_mask.as_mut_ptr()) } < 0 {
             Err(Error::last_os_error())
         } else {
             Ok(Self { mask: unsafe { old_mask.assume_init() } })
         }
     }
 
+    /// Block the signal specified in mask until the returned guard is dropped,
+    /// which restores the old signal mask.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut signal_mask = SignalMask::new();
+    /// signal_mask.add(Signal::Sigint).unwrap();
+    ///
+    /// {
+    ///     let _guard = signal_mask.block_scoped().unwrap();
+    ///     assert!(SignalMask::current().unwrap().is_member(Signal::Sigint).unwrap());
+    /// }
+    ///
+    /// assert!(!SignalMask::current().unwrap().is_member(Signal::Sigint).unwrap());
+    /// ```
+    pub fn block_scoped(&self) -> Result<BlockGuard> {
+        Ok(BlockGuard {
+            old_mask: self.block()?,
+            _not_send: PhantomData,
+        })
+    }
+
+    /// Block the signal specified in mask on the calling thread only and return
+    /// the old signal mask of the thread.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// std::thread::spawn(|| {
+    ///     let mut signal_mask = SignalMask::new();
+    ///     signal_mask.add(Signal::Sigusr1).unwrap();
+    ///     signal_mask.block_thread().unwrap();
+    ///
+    ///     assert!(SignalMask::current().unwrap().is_member(Signal::Sigusr1).unwrap());
+    /// }).join().unwrap();
+    ///
+    /// assert!(!SignalMask::current().unwrap().is_member(Signal::Sigusr1).unwrap());
+    /// ```
+    pub fn block_thread(&self) -> Result<SignalMask> {
+        self.block_thread_with(How::Block)
+    }
+
+    /// Change the signal mask of the calling thread only according to `how`
+    /// and return the old signal mask of the thread.
+    pub fn block_thread_with(&self, how: How) -> Result<SignalMask> {
+        let mut old_mask = std::mem::MaybeUninit::<sigset_t>::uninit();
+
+        let ret = unsafe { pthread_sigmask(how.into(), &self.mask, old_mask.as_mut_ptr()) };
+        if ret != 0 {
+            Err(Error::from_raw_os_error(ret))
+        } else {
+            Ok(Self { mask: unsafe { old_mask.assume_init() } })
+        }
+    }
+
+    /// Retrieve the raw bytes of the underlying `sigset_t`, for dumping and
+    /// comparing masks bit-for-bit.
+    ///
+    /// On Linux, signal `n` is bit `n - 1` of an array of native-endian
+    /// `unsigned long`, the same layout `/proc/self/status` prints as hex.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use async_linux_spec_fd::*;
+    ///
+    /// let mut signal_mask = SignalMask::new();
+    /// signal_mask.add(Signal::Sigusr1).unwrap();
+    ///
+    /// let bit = (libc::SIGUSR1 - 1) as usize;
+    /// let bytes = signal_mask.as_bytes();
+    ///
+    /// # #[cfg(target_endian = "little")]
+    /// assert_eq!(bytes[bit / 8], 1 << (bit % 8));
+    /// assert_eq!(bytes.iter().filter(|byte| **byte != 0).count(), 1);
+    /// ```
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(
+                &self.mask as *const sigset_t as *const u8,
+                std::mem::size_of::<sigset_t>()
+            )
+        }
+    }
+
     /// Retrieved the underlying `sigset_t`.
     pub fn as_sigset(&self) -> &sigset_t {
         &self.mask
@@ -93,3 +349,36 @@ impl SignalMask {
         &mut self.mask
     }
 }
+
+/// Guard returned by `SignalMask::block_scoped`, which restores the old
+/// signal mask on drop.
+///
+/// Since the signal mask is per-thread, it must be dropped on the thread that
+/// created it, thus it is not `Send`.
+///
+/// ```compile_fail
+/// use async_linux_spec_fd::*;
+///
+/// let guard = SignalMask::new().block_scoped().unwrap();
+/// std::thread::spawn(move || drop(guard));
+/// ```
+#[must_use]
+pub struct BlockGuard {
+    old_mask: SignalMask,
+    _not_send: PhantomData<*const ()>,
+}
+impl BlockGuard {
+    /// The signal mask that will be restored on drop.
+    pub fn old_mask(&self) -> &SignalMask {
+        &self.old_mask
+    }
+}
+impl Drop for BlockGuard {
+    fn drop(&mut self) {
+        let ret = unsafe {
+            sigprocmask(SIG_SETMASK, &self.old_mask.mask, null_mut())
+        };
+
+        debug_assert!(ret >= 0, "{}", Error::last_os_error());
+    }
+}
@@ -0,0 +1,228 @@
+use std::io::{Result, Error};
+use std::mem::MaybeUninit;
+use std::ptr::null_mut;
+use std::os::unix::io::AsRawFd;
+use std::time::{Duration, Instant};
+
+use libc::{
+    c_int, clockid_t, itimerspec, timespec, timerfd_create, timerfd_gettime,
+    timerfd_settime, TFD_CLOEXEC, TFD_NONBLOCK, TFD_TIMER_ABSTIME
+};
+
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+
+use crate::fd::{Fd, read_counter};
+
+fn to_timespec(duration: Duration) -> timespec {
+    timespec {
+        tv_sec: duration.as_secs() as libc::time_t,
+        tv_nsec: duration.subsec_nanos() as libc::c_long,
+    }
+}
+
+fn from_timespec(ts: timespec) -> Duration {
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+/// `TimerFd` for async timers that are fds, so that they can be waited on
+/// along with other fds.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use async_linux_spec_fd::*;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let interval = Duration::from_millis(10);
+///
+///     let timerfd = TimerFd::new(libc::CLOCK_MONOTONIC).unwrap();
+///
+///     let start = Instant::now();
+///     timerfd.set(interval, Some(interval)).unwrap();
+///
+///     assert_eq!(timerfd.wait().await.unwrap(), 1);
+///     assert!(start.elapsed() >= interval);
+///
+///     // Expirations are coalesced while the consumer is slow.
+///     std::thread::sleep(interval * 3);
+///     assert!(timerfd.wait().await.unwrap() >= 3);
+/// }
+///
+/// f();
+/// ```
+pub struct TimerFd {
+    inner: AsyncFd<Fd>,
+    clockid: clockid_t,
+}
+impl TimerFd {
+    /// Returns a disarmed `TimerFd` that is close-on-exec.
+    ///
+    ///  * `clockid` - clock used to mark the progress of the timer, e.g.
+    ///    `CLOCK_MONOTONIC`, `CLOCK_REALTIME` or `CLOCK_BOOTTIME`.
+    pub fn new(clockid: clockid_t) -> Result<Self> {
+        let fd = unsafe { timerfd_create(clockid, TFD_NONBLOCK | TFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let fd = unsafe { Fd::new(fd) };
+
+        Ok(Self {
+            inner: AsyncFd::with_interest(fd, Interest::READABLE)?,
+            clockid,
+        })
+    }
+
+    /// Arm the timer to expire after `initial`, then every `interval` if it
+    /// is `Some`.
+    ///
+    /// Setting `initial` to zero disarms the timer.
+    pub fn set(&self, initial: Duration, interval: Option<Duration>) -> Result<()> {
+        self.settime(0, initial, interval)
+    }
+
+    /// Arm the timer to expire at `deadline`, then every `interval` if it
+    /// is `Some`.
+    ///
+    /// `deadline` is converted to an absolute time of the clock of the timer,
+    /// so that the timer is not affected by how long this call takes.
+    ///
+    /// If `deadline` has already passed, the timer expires immediately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let timerfd = TimerFd::new(libc::CLOCK_MONOTONIC).unwrap();
+    ///
+    ///     let deadline = Instant::now() + Duration::from_millis(20);
+    ///     timerfd.set_deadline(deadline, None).unwrap();
+    ///
+    ///     assert_eq!(timerfd.wait().await.unwrap(), 1);
+    ///     let now = Instant::now();
+    ///     assert!(now >= deadline);
+    ///     assert!(now - deadline < Duration::from_millis(100));
+    ///
+    ///     let start = Instant::now();
+    ///     timerfd.set_deadline(start - Duration::from_secs(1), None).unwrap();
+    ///     assert_eq!(timerfd.wait().await.unwrap(), 1);
+    ///     assert!(start.elapsed() < Duration::from_millis(100));
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn set_deadline(&self, deadline: Instant, interval: Option<Duration>) -> Result<()> {
+        let mut now = MaybeUninit::<timespec>::uninit();
+
+        if unsafe { libc::clock_gettime(self.clockid, now.as_mut_ptr()) } < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        // Saturates to `now` if `deadline` has passed, which is never zero
+        // and thus does not disarm the timer.
+        let deadline = from_timespec(unsafe { now.assume_init() }) +
+            deadline.saturating_duration_since(Instant::now());
+
+        self.settime(TFD_TIMER_ABSTIME, deadline, interval)
+    }
+
+    fn settime(&self, flags: c_int, value: Duration, interval: Option<Duration>) -> Result<()> {
+        let new_value = itimerspec {
+            it_interval: to_timespec(interval.unwrap_or_default()),
+            it_value: to_timespec(value),
+        };
+
+        let fd = self.inner.get_ref().as_raw_fd();
+
+        if unsafe { timerfd_settime(fd, flags, &new_value, null_mut()) } < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Change the interval of the timer without touching the time remaining
+    /// until the next expiration.
+    ///
+    /// If the timer is disarmed, it is armed to expire after `interval`.
+    ///
+    /// Setting `interval` to zero turns the timer into a one-shot timer.
+    pub fn set_interval(&self, interval: Duration) -> Result<()> {
+        let (remaining, _) = self.get()?;
+
+        let initial = if remaining == Duration::ZERO {
+            interval
+        } else {
+            remaining
+        };
+
+        self.set(initial, Some(interval))
+    }
+
+    /// Disarm the timer.
+    ///
+    /// A pending `wait` future stays pending until the timer is armed again.
+    pub fn disarm(&self) -> Result<()> {
+        self.set(Duration::ZERO, None)
+    }
+
+    /// Returns the time remaining until the next expiration and the interval,
+    /// both zero if the timer is disarmed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use async_linux_spec_fd::*;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn f() {
+    ///     let timerfd = TimerFd::new(libc::CLOCK_MONOTONIC).unwrap();
+    ///
+    ///     timerfd.set(Duration::from_millis(5), Some(Duration::from_millis(5))).unwrap();
+    ///     assert!(timerfd.wait().await.unwrap() >= 1);
+    ///     assert!(timerfd.wait().await.unwrap() >= 1);
+    ///
+    ///     timerfd.set_interval(Duration::from_secs(10)).unwrap();
+    ///     let (remaining, interval) = timerfd.get().unwrap();
+    ///     assert!(remaining <= Duration::from_millis(5));
+    ///     assert_eq!(interval, Duration::from_secs(10));
+    ///
+    ///     timerfd.disarm().unwrap();
+    ///     assert_eq!(timerfd.get().unwrap(), (Duration::ZERO, Duration::ZERO));
+    /// }
+    ///
+    /// f();
+    /// ```
+    pub fn get(&self) -> Result<(Duration, Duration)> {
+        let mut curr_value = MaybeUninit::<itimerspec>::uninit();
+
+        let fd = self.inner.get_ref().as_raw_fd();
+
+        if unsafe { timerfd_gettime(fd, curr_value.as_mut_ptr()) } < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let curr_value = unsafe { curr_value.assume_init() };
+
+        Ok((from_timespec(curr_value.it_value), from_timespec(curr_value.it_interval)))
+    }
+
+    /// Asynchronously wait for the timer to expire and return the number of
+    /// expirations since the last `wait` or `set`.
+    pub async fn wait(&self) -> Result<u64> {
+        read_counter(&self.inner).await
+    }
+
+    /// Same as `Fd::close`.
+    pub fn close(self) -> Result<()> {
+        self.inner.into_inner().close()
+    }
+}
@@ -1,3 +1,4 @@
+use std::future::Future;
 use std::io::{Result, ErrorKind};
 
 /// auto restart functions failed due to interrpted
@@ -27,3 +28,59 @@ macro_rules! autorestart {
         )
     };
 }
+
+/// auto restart async functions failed due to interrpted
+///
+/// # Example
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::io::{Error, ErrorKind};
+/// use async_linux_spec_fd::utility::autorestart_async;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn f() {
+///     let cnt = Cell::new(0);
+///
+///     let result = autorestart_async(|| async {
+///         cnt.set(cnt.get() + 1);
+///
+///         if cnt.get() <= 2 {
+///             Err(Error::from(ErrorKind::Interrupted))
+///         } else {
+///             Ok(cnt.get())
+///         }
+///     }).await;
+///
+///     assert_eq!(result.unwrap(), 3);
+/// }
+///
+/// f();
+/// ```
+pub async fn autorestart_async<T, F, Fut>(mut f: F)
+    -> Result<T>
+    where F: FnMut() -> Fut,
+          Fut: Future<Output = Result<T>>
+{
+    loop {
+        let ret = f().await;
+
+        if let Err(err) = &ret {
+            if let ErrorKind::Interrupted = err.kind() {
+                continue
+            }
+        }
+
+        break ret
+    }
+}
+
+/// auto restart async functions failed due to interrpted
+#[macro_export]
+macro_rules! autorestart_async {
+    ( { $( $tt:tt )* } ) => {
+        $crate::utility::autorestart_async(
+            || async { $( $tt )* }
+        )
+    };
+}